@@ -0,0 +1,452 @@
+// Stable reference to a node. The generation changes whenever a slot is reused, so a handle to a
+// removed node is rejected rather than silently pointing at its replacement. Handles must only be
+// passed to the list that created them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeHandle {
+    index: usize,
+    generation: u32,
+}
+
+struct Slot<N> {
+    generation: u32,
+    node: Option<N>,
+}
+
+// Nodes stored in one Vec; vacated slots are recycled through `free`.
+struct Arena<N> {
+    slots: Vec<Slot<N>>,
+    free: Vec<usize>,
+}
+
+impl<N> Arena<N> {
+    fn new() -> Self {
+        Arena {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, node: N) -> NodeHandle {
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.node = Some(node);
+                NodeHandle { index, generation: slot.generation }
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, node: Some(node) });
+                NodeHandle { index: self.slots.len() - 1, generation: 0 }
+            }
+        }
+    }
+
+    fn handle(&self, index: usize) -> NodeHandle {
+        NodeHandle { index, generation: self.slots[index].generation }
+    }
+
+    fn contains(&self, handle: NodeHandle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    fn get(&self, handle: NodeHandle) -> Option<&N> {
+        let slot = self.slots.get(handle.index)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.node.as_ref()
+    }
+
+    fn get_mut(&mut self, handle: NodeHandle) -> Option<&mut N> {
+        let slot = self.slots.get_mut(handle.index)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.node.as_mut()
+    }
+
+    fn remove(&mut self, index: usize) -> N {
+        let slot = &mut self.slots[index];
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(index);
+        slot.node.take().unwrap()
+    }
+
+    // Drops every node but keeps the allocation; all outstanding handles become stale.
+    fn clear(&mut self) {
+        self.free.clear();
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.node.take().is_some() {
+                slot.generation = slot.generation.wrapping_add(1);
+            }
+            self.free.push(index);
+        }
+    }
+
+    fn node(&self, index: usize) -> &N {
+        self.slots[index].node.as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut N {
+        self.slots[index].node.as_mut().unwrap()
+    }
+}
+
+struct ArenaNode<T> {
+    value: T,
+    next: Option<usize>,
+}
+
+pub struct ArenaList<T> {
+    arena: Arena<ArenaNode<T>>,
+    head: Option<usize>,
+    length: usize,
+}
+
+impl<T> ArenaList<T> {
+    pub fn new() -> Self {
+        ArenaList {
+            arena: Arena::new(),
+            head: None,
+            length: 0,
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn front(&self) -> Option<NodeHandle> {
+        self.head.map(|index| self.arena.handle(index))
+    }
+
+    pub fn next(&self, handle: NodeHandle) -> Option<NodeHandle> {
+        let next = self.arena.get(handle)?.next?;
+        Some(self.arena.handle(next))
+    }
+
+    pub fn get(&self, handle: NodeHandle) -> Option<&T> {
+        self.arena.get(handle).map(|n| &n.value)
+    }
+
+    pub fn get_mut(&mut self, handle: NodeHandle) -> Option<&mut T> {
+        self.arena.get_mut(handle).map(|n| &mut n.value)
+    }
+
+    pub fn push_front(&mut self, value: T) -> NodeHandle {
+        let handle = self.arena.insert(ArenaNode { value, next: self.head });
+        self.head = Some(handle.index);
+        self.length += 1;
+        handle
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let head = self.head?;
+        let node = self.arena.remove(head);
+        self.head = node.next;
+        self.length -= 1;
+        Some(node.value)
+    }
+
+    pub fn insert_after(&mut self, handle: NodeHandle, value: T) -> Result<NodeHandle, ()> {
+        let next = self.arena.get(handle).ok_or(())?.next;
+        let new = self.arena.insert(ArenaNode { value, next });
+        self.arena.node_mut(handle.index).next = Some(new.index);
+        self.length += 1;
+        Ok(new)
+    }
+
+    // A singly linked node can only be unlinked through its predecessor.
+    pub fn remove_after(&mut self, handle: NodeHandle) -> Option<T> {
+        let target = self.arena.get(handle)?.next?;
+        let node = self.arena.remove(target);
+        self.arena.node_mut(handle.index).next = node.next;
+        self.length -= 1;
+        Some(node.value)
+    }
+
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.head = None;
+        self.length = 0;
+    }
+
+    pub fn iter(&self) -> ArenaListIter<'_, T> {
+        ArenaListIter { list: self, next: self.head }
+    }
+}
+
+pub struct ArenaListIter<'a, T> {
+    list: &'a ArenaList<T>,
+    next: Option<usize>,
+}
+
+impl<'a, T> Iterator for ArenaListIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.list.arena.node(self.next?);
+        self.next = node.next;
+        Some(&node.value)
+    }
+}
+
+struct ArenaDoublyNode<T> {
+    value: T,
+    next: Option<usize>,
+    previous: Option<usize>,
+}
+
+pub struct ArenaDoublyLinkedList<T> {
+    arena: Arena<ArenaDoublyNode<T>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    length: usize,
+}
+
+impl<T> ArenaDoublyLinkedList<T> {
+    pub fn new() -> Self {
+        ArenaDoublyLinkedList {
+            arena: Arena::new(),
+            head: None,
+            tail: None,
+            length: 0,
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn contains(&self, handle: NodeHandle) -> bool {
+        self.arena.contains(handle)
+    }
+
+    pub fn front(&self) -> Option<NodeHandle> {
+        self.head.map(|index| self.arena.handle(index))
+    }
+
+    pub fn back(&self) -> Option<NodeHandle> {
+        self.tail.map(|index| self.arena.handle(index))
+    }
+
+    pub fn next(&self, handle: NodeHandle) -> Option<NodeHandle> {
+        let next = self.arena.get(handle)?.next?;
+        Some(self.arena.handle(next))
+    }
+
+    pub fn previous(&self, handle: NodeHandle) -> Option<NodeHandle> {
+        let previous = self.arena.get(handle)?.previous?;
+        Some(self.arena.handle(previous))
+    }
+
+    pub fn get(&self, handle: NodeHandle) -> Option<&T> {
+        self.arena.get(handle).map(|n| &n.value)
+    }
+
+    pub fn get_mut(&mut self, handle: NodeHandle) -> Option<&mut T> {
+        self.arena.get_mut(handle).map(|n| &mut n.value)
+    }
+
+    // Creates a node between `previous` and `next` and points both neighbours (or the ends) at it.
+    fn link(&mut self, value: T, previous: Option<usize>, next: Option<usize>) -> NodeHandle {
+        let handle = self.arena.insert(ArenaDoublyNode { value, next, previous });
+        match previous {
+            Some(p) => self.arena.node_mut(p).next = Some(handle.index),
+            None => self.head = Some(handle.index),
+        }
+        match next {
+            Some(n) => self.arena.node_mut(n).previous = Some(handle.index),
+            None => self.tail = Some(handle.index),
+        }
+        self.length += 1;
+        handle
+    }
+
+    fn unlink(&mut self, index: usize) -> T {
+        let node = self.arena.remove(index);
+        match node.previous {
+            Some(p) => self.arena.node_mut(p).next = node.next,
+            None => self.head = node.next,
+        }
+        match node.next {
+            Some(n) => self.arena.node_mut(n).previous = node.previous,
+            None => self.tail = node.previous,
+        }
+        self.length -= 1;
+        node.value
+    }
+
+    pub fn push_front(&mut self, value: T) -> NodeHandle {
+        self.link(value, None, self.head)
+    }
+
+    pub fn push_back(&mut self, value: T) -> NodeHandle {
+        self.link(value, self.tail, None)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let head = self.head?;
+        Some(self.unlink(head))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let tail = self.tail?;
+        Some(self.unlink(tail))
+    }
+
+    pub fn insert_before(&mut self, handle: NodeHandle, value: T) -> Result<NodeHandle, ()> {
+        let previous = self.arena.get(handle).ok_or(())?.previous;
+        Ok(self.link(value, previous, Some(handle.index)))
+    }
+
+    pub fn insert_after(&mut self, handle: NodeHandle, value: T) -> Result<NodeHandle, ()> {
+        let next = self.arena.get(handle).ok_or(())?.next;
+        Ok(self.link(value, Some(handle.index), next))
+    }
+
+    // O(1); returns None for stale handles.
+    pub fn remove(&mut self, handle: NodeHandle) -> Option<T> {
+        if !self.arena.contains(handle) {
+            return None;
+        }
+        Some(self.unlink(handle.index))
+    }
+
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.head = None;
+        self.tail = None;
+        self.length = 0;
+    }
+
+    pub fn iter(&self) -> ArenaDoublyIter<'_, T> {
+        ArenaDoublyIter { list: self, next: self.head }
+    }
+}
+
+pub struct ArenaDoublyIter<'a, T> {
+    list: &'a ArenaDoublyLinkedList<T>,
+    next: Option<usize>,
+}
+
+impl<'a, T> Iterator for ArenaDoublyIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.list.arena.node(self.next?);
+        self.next = node.next;
+        Some(&node.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn singly_linked() {
+        let mut list = ArenaList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+
+        let three = list.push_front(3);
+        let one = list.push_front(1);
+        let two = list.insert_after(one, 2).unwrap();
+        assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(list.get_length(), 3);
+        assert_eq!(list.front(), Some(one));
+        assert_eq!(list.next(one), Some(two));
+
+        assert_eq!(list.remove_after(one), Some(2));
+        assert_eq!(list.get(two), None);
+        assert_eq!(list.insert_after(two, 5), Err(()));
+        assert_eq!(list.remove_after(three), None);
+
+        if let Some(v) = list.get_mut(three) {
+            *v = 4;
+        }
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.iter().cloned().collect::<Vec<_>>(), vec![4]);
+
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.get(three), None);
+    }
+
+    #[test]
+    fn stale_handles_after_slot_reuse() {
+        let mut list = ArenaDoublyLinkedList::new();
+        let first = list.push_back("first");
+        assert_eq!(list.remove(first), Some("first"));
+
+        let second = list.push_back("second");
+        assert_eq!(second.index, first.index);
+        assert_eq!(list.get(first), None);
+        assert_eq!(list.remove(first), None);
+        assert_eq!(list.insert_after(first, "third"), Err(()));
+        assert_eq!(list.get(second), Some(&"second"));
+    }
+
+    #[test]
+    fn doubly_linked_against_vec() {
+        let mut rng = rand::rng();
+        let mut list = ArenaDoublyLinkedList::new();
+        let mut oracle: Vec<(NodeHandle, u32)> = Vec::new();
+
+        for i in 0..5000 {
+            match rng.random_range(0..7) {
+                0 => oracle.insert(0, (list.push_front(i), i)),
+                1 => oracle.push((list.push_back(i), i)),
+                2 if !oracle.is_empty() => {
+                    let at = rng.random_range(0..oracle.len());
+                    let handle = list.insert_before(oracle[at].0, i).unwrap();
+                    oracle.insert(at, (handle, i));
+                }
+                3 if !oracle.is_empty() => {
+                    let at = rng.random_range(0..oracle.len());
+                    let handle = list.insert_after(oracle[at].0, i).unwrap();
+                    oracle.insert(at + 1, (handle, i));
+                }
+                4 if !oracle.is_empty() => {
+                    let (handle, value) = oracle.remove(rng.random_range(0..oracle.len()));
+                    assert_eq!(list.remove(handle), Some(value));
+                    assert!(!list.contains(handle));
+                }
+                5 => assert_eq!(list.pop_front(), (!oracle.is_empty()).then(|| oracle.remove(0).1)),
+                _ => assert_eq!(list.pop_back(), oracle.pop().map(|(_, v)| v)),
+            }
+            assert_eq!(list.get_length(), oracle.len());
+        }
+
+        assert!(list.iter().eq(oracle.iter().map(|(_, v)| v)));
+        for window in oracle.windows(2) {
+            assert_eq!(list.next(window[0].0), Some(window[1].0));
+            assert_eq!(list.previous(window[1].0), Some(window[0].0));
+        }
+        assert_eq!(list.front(), oracle.first().map(|(h, _)| *h));
+        assert_eq!(list.back(), oracle.last().map(|(h, _)| *h));
+    }
+
+    #[test]
+    fn bulk_drop() {
+        let mut list = ArenaDoublyLinkedList::new();
+        for i in 0..1_000_000 {
+            list.push_back(i);
+        }
+        let last = list.back().unwrap();
+        list.clear();
+        assert_eq!(list.get(last), None);
+        list.push_back(1);
+        drop(list);
+    }
+}
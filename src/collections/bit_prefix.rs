@@ -0,0 +1,128 @@
+use bitvec::prelude::*;
+
+type KeySlice = BitSlice<u8, Msb0>;
+
+// Length of the common prefix of two bit strings. Compares 64 bits at a time: `load_be` reads a
+// window at any bit offset with the first bit as the most significant, so the first differing bit
+// is the leading zero count of the XOR.
+pub(crate) fn matching_prefix_length(a: &KeySlice, b: &KeySlice) -> usize {
+    let length = a.len().min(b.len());
+    let mut i = 0;
+
+    while i + 64 <= length {
+        let difference = a[i..i + 64].load_be::<u64>() ^ b[i..i + 64].load_be::<u64>();
+        if difference != 0 {
+            return i + difference.leading_zeros() as usize;
+        }
+        i += 64;
+    }
+
+    let rest = length - i;
+    if rest > 0 {
+        // The tail is loaded into the low bits, shift it up so leading zeros count from its start
+        let difference = (a[i..length].load_be::<u64>() ^ b[i..length].load_be::<u64>()) << (64 - rest);
+        if difference != 0 {
+            return i + difference.leading_zeros() as usize;
+        }
+    }
+    length
+}
+
+// Number of positions where two bit strings of the same length differ, 64 bits at a time
+pub(crate) fn hamming_distance(a: &KeySlice, b: &KeySlice) -> usize {
+    debug_assert_eq!(a.len(), b.len());
+    let mut distance = 0;
+    let mut i = 0;
+    while i < a.len() {
+        let end = (i + 64).min(a.len());
+        distance += (a[i..end].load_be::<u64>() ^ b[i..end].load_be::<u64>()).count_ones() as usize;
+        i = end;
+    }
+    distance
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::{Rng, RngCore};
+    use std::time::Instant;
+
+    fn bit_by_bit(a: &KeySlice, b: &KeySlice) -> usize {
+        a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count()
+    }
+
+    #[test]
+    fn matches_bit_by_bit_at_any_offset() {
+        let mut rng = rand::rng();
+        for _ in 0..5000 {
+            let mut bytes = [0u8; 40];
+            rng.fill_bytes(&mut bytes);
+            let mut other = bytes;
+            // Flip one bit, or none, somewhere in the copy
+            if rng.random_bool(0.8) {
+                let bit = rng.random_range(0..320);
+                other.view_bits_mut::<Msb0>().set(bit, !bytes.view_bits::<Msb0>()[bit]);
+            }
+
+            let a = &bytes.view_bits::<Msb0>()[rng.random_range(0..16)..];
+            let b = &other.view_bits::<Msb0>()[rng.random_range(0..16)..];
+            let a = &a[..rng.random_range(0..=a.len())];
+            assert_eq!(matching_prefix_length(a, b), bit_by_bit(a, b));
+
+            let start = rng.random_range(0..64);
+            let end = rng.random_range(start..=320);
+            let a = &bytes.view_bits::<Msb0>()[start..end];
+            let b = &other.view_bits::<Msb0>()[start..];
+            assert_eq!(matching_prefix_length(a, b), bit_by_bit(a, b));
+        }
+        assert_eq!(matching_prefix_length(bits![u8, Msb0;], bits![u8, Msb0; 1]), 0);
+    }
+
+    #[test]
+    fn hamming_distance_matches_bit_by_bit() {
+        let mut rng = rand::rng();
+        for _ in 0..2000 {
+            let mut bytes = [0u8; 40];
+            let mut other = [0u8; 40];
+            rng.fill_bytes(&mut bytes);
+            rng.fill_bytes(&mut other);
+            let length = rng.random_range(0..=300);
+            let a = &bytes.view_bits::<Msb0>()[rng.random_range(0..20)..][..length];
+            let b = &other.view_bits::<Msb0>()[rng.random_range(0..20)..][..length];
+            assert_eq!(hamming_distance(a, b), a.iter().zip(b.iter()).filter(|(x, y)| x != y).count());
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn benchmark_against_bit_by_bit() {
+        const SIZE: usize = 4096;
+        const ROUNDS: usize = 2000;
+        let mut rng = rand::rng();
+        let mut bytes = vec![0u8; SIZE];
+        rng.fill_bytes(&mut bytes);
+        let a = bytes.view_bits::<Msb0>().to_bitvec();
+        // Identical keys force a full comparison, the worst case for a successful search
+        let b = a.clone();
+        let unaligned = &b[3..];
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            assert_eq!(bit_by_bit(&a, &b), SIZE * 8);
+        }
+        println!("{} bit by bit comparisons took {:?}", ROUNDS, start.elapsed());
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            assert_eq!(matching_prefix_length(&a, &b), SIZE * 8);
+        }
+        println!("{} aligned word comparisons took {:?}", ROUNDS, start.elapsed());
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            matching_prefix_length(&a[3..], unaligned);
+        }
+        println!("{} unaligned word comparisons took {:?}", ROUNDS, start.elapsed());
+    }
+}
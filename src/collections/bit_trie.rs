@@ -0,0 +1,197 @@
+use bitvec::prelude::*;
+use crate::collections::compact_patricia::CompactPatriciaTree;
+use crate::collections::loopy_patricia::LoopyPatriciaTree;
+use crate::collections::patricia::PatriciaTree;
+
+type Key = BitVec<u8, Msb0>;
+
+// A set of bit strings. Both Patricia trees implement it so they can be checked against each
+// other; outside this module use `PatriciaSet`.
+pub trait BitTrie {
+    // Returns false if the key was already present
+    fn insert(&mut self, key: &Key) -> bool;
+    fn search(&self, key: &Key) -> bool;
+    // Returns false if the key was not present
+    fn remove(&mut self, key: &Key) -> bool;
+    fn get_length(&self) -> usize;
+    // Keys in order, a key before its extensions and 0 before 1
+    fn keys(&self) -> impl Iterator<Item = Key> + '_;
+
+    fn is_empty(&self) -> bool {
+        self.get_length() == 0
+    }
+}
+
+// The iterative tree avoids deep recursion on long keys and carries the map, range and prefix
+// operations, so it is the one to use.
+pub type PatriciaSet = LoopyPatriciaTree;
+
+impl BitTrie for PatriciaTree {
+    fn insert(&mut self, key: &Key) -> bool {
+        PatriciaTree::insert(self, key)
+    }
+
+    fn search(&self, key: &Key) -> bool {
+        PatriciaTree::search(self, key)
+    }
+
+    fn remove(&mut self, key: &Key) -> bool {
+        PatriciaTree::remove(self, key)
+    }
+
+    fn get_length(&self) -> usize {
+        PatriciaTree::get_length(self)
+    }
+
+    fn keys(&self) -> impl Iterator<Item = Key> + '_ {
+        PatriciaTree::iter(self)
+    }
+}
+
+impl BitTrie for LoopyPatriciaTree {
+    fn insert(&mut self, key: &Key) -> bool {
+        LoopyPatriciaTree::insert(self, key)
+    }
+
+    fn search(&self, key: &Key) -> bool {
+        LoopyPatriciaTree::search(self, key)
+    }
+
+    fn remove(&mut self, key: &Key) -> bool {
+        LoopyPatriciaTree::remove(self, key)
+    }
+
+    fn get_length(&self) -> usize {
+        LoopyPatriciaTree::get_length(self)
+    }
+
+    fn keys(&self) -> impl Iterator<Item = Key> + '_ {
+        LoopyPatriciaTree::iter(self)
+    }
+}
+
+impl BitTrie for CompactPatriciaTree {
+    fn insert(&mut self, key: &Key) -> bool {
+        CompactPatriciaTree::insert(self, key)
+    }
+
+    fn search(&self, key: &Key) -> bool {
+        CompactPatriciaTree::search(self, key)
+    }
+
+    fn remove(&mut self, key: &Key) -> bool {
+        CompactPatriciaTree::remove(self, key)
+    }
+
+    fn get_length(&self) -> usize {
+        CompactPatriciaTree::get_length(self)
+    }
+
+    fn keys(&self) -> impl Iterator<Item = Key> + '_ {
+        CompactPatriciaTree::iter(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, RngCore, SeedableRng};
+    use std::collections::BTreeSet;
+
+    // Short keys over a tiny alphabet, so the empty key, equal keys and keys that are prefixes of
+    // each other all come up often.
+    fn short_key(rng: &mut StdRng) -> Key {
+        let length = rng.random_range(0..10);
+        (0..length).map(|_| rng.random_bool(0.5)).collect()
+    }
+
+    fn long_key(rng: &mut StdRng) -> Key {
+        let mut bytes = [0u8; 64];
+        rng.fill_bytes(&mut bytes);
+        let shared = rng.random_range(0..512);
+        // Half of the keys share a common run of bits so that compressed paths get split
+        let mut key: Key = bytes.view_bits::<Msb0>()[..shared].to_bitvec();
+        if rng.random_bool(0.5) {
+            key = bits![u8, Msb0; 1; 300].to_bitvec();
+            key.extend_from_bitslice(&bytes.view_bits::<Msb0>()[..shared / 4]);
+        }
+        key
+    }
+
+    fn conformance<T>(new: fn() -> T, seed: u64, key: fn(&mut StdRng) -> Key, steps: usize) where T: BitTrie {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut trie = new();
+        let mut oracle = BTreeSet::new();
+        assert!(trie.is_empty());
+        assert!(!trie.search(&Key::new()));
+
+        for _ in 0..steps {
+            let k = key(&mut rng);
+            match rng.random_range(0..3) {
+                0 | 1 => assert_eq!(trie.insert(&k), oracle.insert(k)),
+                _ => assert_eq!(trie.remove(&k), oracle.remove(&k)),
+            }
+            let probe = key(&mut rng);
+            assert_eq!(trie.search(&probe), oracle.contains(&probe));
+            assert_eq!(trie.get_length(), oracle.len());
+        }
+        assert!(trie.keys().eq(oracle.iter().cloned()));
+
+        for k in &oracle {
+            assert!(trie.remove(k));
+        }
+        assert!(trie.is_empty());
+        assert_eq!(trie.keys().count(), 0);
+    }
+
+    #[test]
+    fn recursive_tree_conformance() {
+        conformance(PatriciaTree::new, 1, short_key, 3000);
+        conformance(PatriciaTree::new, 2, long_key, 500);
+    }
+
+    #[test]
+    fn iterative_tree_conformance() {
+        conformance(LoopyPatriciaTree::new, 1, short_key, 3000);
+        conformance(PatriciaSet::new, 2, long_key, 500);
+    }
+
+    #[test]
+    fn compact_tree_conformance() {
+        conformance(CompactPatriciaTree::new, 1, short_key, 3000);
+        conformance(CompactPatriciaTree::new, 2, long_key, 500);
+    }
+
+    // Feeds the same operations to every tree and requires identical answers at every step.
+    #[test]
+    fn trees_agree() {
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut recursive = PatriciaTree::new();
+            let mut iterative = LoopyPatriciaTree::new();
+            let mut compact = CompactPatriciaTree::new();
+
+            for step in 0..500 {
+                let k = if step % 2 == 0 { short_key(&mut rng) } else { long_key(&mut rng) };
+                if rng.random_bool(0.6) {
+                    let added = BitTrie::insert(&mut recursive, &k);
+                    assert_eq!(added, BitTrie::insert(&mut iterative, &k));
+                    assert_eq!(added, BitTrie::insert(&mut compact, &k));
+                } else {
+                    let removed = BitTrie::remove(&mut recursive, &k);
+                    assert_eq!(removed, BitTrie::remove(&mut iterative, &k));
+                    assert_eq!(removed, BitTrie::remove(&mut compact, &k));
+                }
+                let found = BitTrie::search(&recursive, &k);
+                assert_eq!(found, BitTrie::search(&iterative, &k));
+                assert_eq!(found, BitTrie::search(&compact, &k));
+                assert_eq!(BitTrie::get_length(&recursive), BitTrie::get_length(&iterative));
+                assert_eq!(BitTrie::get_length(&recursive), BitTrie::get_length(&compact));
+            }
+            assert!(BitTrie::keys(&recursive).eq(BitTrie::keys(&iterative)));
+            assert!(BitTrie::keys(&recursive).eq(BitTrie::keys(&compact)));
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use bitvec::prelude::*;
+use std::mem::size_of;
+use crate::collections::bit_prefix::matching_prefix_length;
+use crate::collections::loopy_patricia::LoopyPatriciaTree;
+
+type Key = BitVec<u8, Msb0>;
+type KeySlice = BitSlice<u8, Msb0>;
+
+pub(crate) const NIL: u32 = u32::MAX;
+
+// The key segment lives in the tree's bit pool at `offset`, children are arena indices.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CompactNode {
+    pub(crate) children: [u32; 2],
+    pub(crate) offset: usize,
+    pub(crate) length: u32,
+    pub(crate) end: bool,
+}
+
+// Same shape as LoopyPatriciaTree, but nodes sit in one Vec and all key segments are slices of
+// a single shared bit pool. Splitting a node only cuts its segment in two; bits are appended to
+// the pool for new leaves, and for merges whose two segments are not already adjacent. Removing
+// keys leaves dead bits behind, and the pool is repacked once they outnumber the live ones.
+#[derive(Debug)]
+pub struct CompactPatriciaTree {
+    pub(crate) nodes: Vec<CompactNode>,
+    pub(crate) free: Vec<u32>,
+    pub(crate) bits: Key,
+    // Bits of the pool no live segment points at
+    pub(crate) garbage: usize,
+    pub(crate) root: u32,
+    pub(crate) length: usize,
+}
+
+impl CompactPatriciaTree {
+    pub fn new() -> Self {
+        CompactPatriciaTree {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            bits: Key::new(),
+            garbage: 0,
+            root: NIL,
+            length: 0,
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    // Heap and inline bytes held by the tree, counting reserved capacity
+    pub fn memory_usage(&self) -> usize {
+        size_of::<Self>()
+            + self.nodes.capacity() * size_of::<CompactNode>()
+            + self.free.capacity() * size_of::<u32>()
+            + self.bits.capacity().div_ceil(8)
+    }
+
+    pub(crate) fn segment(&self, index: u32) -> &KeySlice {
+        let node = &self.nodes[index as usize];
+        &self.bits[node.offset..node.offset + node.length as usize]
+    }
+
+    fn alloc(&mut self, node: CompactNode) -> u32 {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index as usize] = node;
+                index
+            }
+            None => {
+                assert!(self.nodes.len() < NIL as usize, "too many nodes for u32 indices");
+                self.nodes.push(node);
+                (self.nodes.len() - 1) as u32
+            }
+        }
+    }
+
+    fn new_leaf(&mut self, segment: &KeySlice) -> u32 {
+        let offset = self.bits.len();
+        self.bits.extend_from_bitslice(segment);
+        self.alloc(CompactNode {
+            children: [NIL, NIL],
+            offset,
+            length: segment.len() as u32,
+            end: true,
+        })
+    }
+
+    // Cuts the node's segment after `at` bits, moving the rest of the node into a new child
+    fn split(&mut self, index: u32, at: usize) {
+        let node = self.nodes[index as usize];
+        let child = CompactNode {
+            offset: node.offset + at,
+            length: node.length - at as u32,
+            ..node
+        };
+        let bit = self.bits[child.offset] as usize;
+        let child = self.alloc(child);
+
+        let node = &mut self.nodes[index as usize];
+        node.length = at as u32;
+        node.children = [NIL, NIL];
+        node.children[bit] = child;
+        node.end = false;
+    }
+
+    // Returns false if the key was already in the tree
+    pub fn insert(&mut self, key: &Key) -> bool {
+        if self.root == NIL {
+            self.root = self.new_leaf(key);
+            self.length += 1;
+            return true;
+        }
+
+        let mut index = self.root;
+        let mut consumed = 0;
+        loop {
+            let rest = &key[consumed..];
+            let segment_length = self.nodes[index as usize].length as usize;
+            let i = matching_prefix_length(self.segment(index), rest);
+
+            if i == rest.len() {
+                if i != segment_length {
+                    self.split(index, i);
+                }
+                let node = &mut self.nodes[index as usize];
+                let added = !node.end;
+                node.end = true;
+                self.length += added as usize;
+                return added;
+            }
+
+            let bit = rest[i] as usize;
+            if i == segment_length {
+                let child = self.nodes[index as usize].children[bit];
+                if child != NIL {
+                    index = child;
+                    consumed += i;
+                    continue;
+                }
+            } else {
+                self.split(index, i);
+            }
+            let leaf = self.new_leaf(&rest[i..]);
+            self.nodes[index as usize].children[bit] = leaf;
+            self.length += 1;
+            return true;
+        }
+    }
+
+    // Indices from the root to the node whose path spells the key, whether or not it ends there
+    fn find_path(&self, key: &Key) -> Option<Vec<u32>> {
+        if self.root == NIL {
+            return None;
+        }
+        let mut path = vec![self.root];
+        let mut consumed = 0;
+        loop {
+            let index = *path.last().unwrap();
+            let rest = &key[consumed..];
+            let segment = self.segment(index);
+            let i = matching_prefix_length(segment, rest);
+            if i != segment.len() {
+                return None;
+            }
+            if i == rest.len() {
+                return Some(path);
+            }
+            let child = self.nodes[index as usize].children[rest[i] as usize];
+            if child == NIL {
+                return None;
+            }
+            path.push(child);
+            consumed += i;
+        }
+    }
+
+    pub fn search(&self, key: &Key) -> bool {
+        match self.find_path(key) {
+            Some(path) => self.nodes[*path.last().unwrap() as usize].end,
+            None => false,
+        }
+    }
+
+    // Remove a key from the tree, returns false if it was not there
+    pub fn remove(&mut self, key: &Key) -> bool {
+        let path = match self.find_path(key) {
+            Some(path) if self.nodes[*path.last().unwrap() as usize].end => path,
+            _ => return false,
+        };
+        self.length -= 1;
+        let target = path[path.len() - 1];
+        self.nodes[target as usize].end = false;
+
+        // Only the target and its parent can change shape
+        let replacement = self.compress(target);
+        if path.len() == 1 {
+            self.root = replacement;
+        } else {
+            let parent = path[path.len() - 2];
+            self.relink(parent, target, replacement);
+            let replacement = self.compress(parent);
+            if path.len() == 2 {
+                self.root = replacement;
+            } else {
+                self.relink(path[path.len() - 3], parent, replacement);
+            }
+        }
+        if self.garbage * 2 > self.bits.len() {
+            self.repack();
+        }
+        true
+    }
+
+    // The live nodes renumbered in pre-order, with their segments copied into a fresh pool
+    pub(crate) fn repacked(&self) -> (Vec<CompactNode>, Key) {
+        let mut order = Vec::new();
+        let mut new_index = vec![NIL; self.nodes.len()];
+        let mut stack: Vec<u32> = if self.root == NIL { Vec::new() } else { vec![self.root] };
+        while let Some(index) = stack.pop() {
+            new_index[index as usize] = order.len() as u32;
+            order.push(index);
+            for child in self.nodes[index as usize].children.iter().rev() {
+                if *child != NIL {
+                    stack.push(*child);
+                }
+            }
+        }
+
+        let mut bits = Key::new();
+        let nodes = order
+            .iter()
+            .map(|index| {
+                let node = self.nodes[*index as usize];
+                let offset = bits.len();
+                bits.extend_from_bitslice(self.segment(*index));
+                CompactNode {
+                    children: node.children.map(|child| if child == NIL { NIL } else { new_index[child as usize] }),
+                    offset,
+                    ..node
+                }
+            })
+            .collect();
+        (nodes, bits)
+    }
+
+    fn repack(&mut self) {
+        let (nodes, bits) = self.repacked();
+        self.root = if nodes.is_empty() { NIL } else { 0 };
+        self.nodes = nodes;
+        self.bits = bits;
+        self.free.clear();
+        self.garbage = 0;
+    }
+
+    fn relink(&mut self, parent: u32, old: u32, new: u32) {
+        for child in self.nodes[parent as usize].children.iter_mut() {
+            if *child == old {
+                *child = new;
+            }
+        }
+    }
+
+    // BitVec's in-place copies reject ranges that end at the last bit, so go through a copy
+    fn copy_to_end(&mut self, offset: usize, length: u32) {
+        let segment = self.bits[offset..offset + length as usize].to_bitvec();
+        self.bits.extend_from_bitslice(&segment);
+    }
+
+    // Frees a node that no longer leads to a key and folds a keyless node with one child into
+    // it. Returns what the parent should now point at.
+    fn compress(&mut self, index: u32) -> u32 {
+        let node = self.nodes[index as usize];
+        if node.end {
+            return index;
+        }
+        let only = match node.children {
+            [NIL, NIL] => {
+                self.free.push(index);
+                self.garbage += node.length as usize;
+                return NIL;
+            }
+            [only, NIL] | [NIL, only] => only,
+            _ => return index,
+        };
+
+        let child = self.nodes[only as usize];
+        let offset = if node.offset + node.length as usize == child.offset {
+            node.offset
+        } else {
+            let offset = self.bits.len();
+            self.copy_to_end(node.offset, node.length);
+            self.copy_to_end(child.offset, child.length);
+            self.garbage += (node.length + child.length) as usize;
+            offset
+        };
+        self.nodes[index as usize] = CompactNode {
+            offset,
+            length: node.length + child.length,
+            ..child
+        };
+        self.free.push(only);
+        index
+    }
+
+    // Keys in order, a key before its extensions and 0 before 1
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            tree: self,
+            stack: if self.root == NIL { Vec::new() } else { vec![(self.root, 0)] },
+            key: Key::new(),
+        }
+    }
+}
+
+// Keys come out in order, so each insert only appends a leaf past the previous key's path
+impl From<&LoopyPatriciaTree> for CompactPatriciaTree {
+    fn from(tree: &LoopyPatriciaTree) -> Self {
+        let mut compact = CompactPatriciaTree::new();
+        for key in tree.iter() {
+            compact.insert(&key);
+        }
+        compact
+    }
+}
+
+impl From<&CompactPatriciaTree> for LoopyPatriciaTree {
+    fn from(tree: &CompactPatriciaTree) -> Self {
+        LoopyPatriciaTree::from_sorted_iter(tree.iter()).expect("compact tree keys are sorted")
+    }
+}
+
+pub struct Iter<'a> {
+    tree: &'a CompactPatriciaTree,
+    stack: Vec<(u32, usize)>,
+    key: Key,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Key;
+
+    fn next(&mut self) -> Option<Key> {
+        while let Some((index, depth)) = self.stack.pop() {
+            self.key.truncate(depth);
+            self.key.extend_from_bitslice(self.tree.segment(index));
+            let node = &self.tree.nodes[index as usize];
+            for child in node.children.iter().rev() {
+                if *child != NIL {
+                    self.stack.push((*child, self.key.len()));
+                }
+            }
+            if node.end {
+                return Some(self.key.clone());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use crate::collections::bit_trie::PatriciaSet;
+    use rand::{Rng, RngCore};
+
+    #[test]
+    fn splits_share_the_pool() {
+        let mut tree = CompactPatriciaTree::new();
+        let key = |bits: &str| -> Key { bits.chars().map(|c| c == '1').collect() };
+        tree.insert(&key("110011"));
+        tree.insert(&key("11"));
+        tree.insert(&key("1100"));
+        // Both prefixes only cut the first segment
+        assert_eq!(tree.bits.len(), 6);
+        assert_eq!(tree.nodes.len(), 3);
+
+        tree.insert(&key("111"));
+        assert_eq!(tree.bits.len(), 7);
+        assert!(tree.remove(&key("11")));
+        assert!(tree.remove(&key("111")));
+        // 11 and 00 are adjacent in the pool, so folding them back together copies nothing
+        assert_eq!(tree.bits.len(), 7);
+        assert_eq!(tree.free.len(), 2);
+        assert_eq!(tree.iter().collect::<Vec<_>>(), vec![key("1100"), key("110011")]);
+
+        // Splitting the root for 0 and adding the leaf reuse both freed slots
+        tree.insert(&key("0"));
+        assert!(tree.free.is_empty());
+        assert_eq!(tree.nodes.len(), 4);
+    }
+
+    #[test]
+    fn uses_less_memory_than_boxed_nodes() {
+        const SIZE: usize = 256;
+        let mut rng = rand::rng();
+        let mut compact = CompactPatriciaTree::new();
+        let mut boxed = PatriciaSet::new();
+
+        for _ in 0..2000 {
+            let mut bytes = [0u8; SIZE];
+            rng.fill_bytes(&mut bytes);
+            let key = bytes.view_bits::<Msb0>().to_bitvec();
+            compact.insert(&key);
+            boxed.insert(&key);
+        }
+        let keys = 2000 * SIZE;
+        assert!(compact.memory_usage() < boxed.memory_usage());
+        // The pool may have up to double the key bits reserved
+        assert!(compact.memory_usage() < keys * 2);
+    }
+
+
+    #[test]
+    fn churn_does_not_grow_the_pool() {
+        let mut rng = rand::rng();
+        let mut tree = CompactPatriciaTree::new();
+        let mut live: Vec<Key> = Vec::new();
+        let segment_bits = |tree: &CompactPatriciaTree| -> usize {
+            let mut total = 0;
+            let mut stack: Vec<u32> = if tree.root == NIL { Vec::new() } else { vec![tree.root] };
+            while let Some(index) = stack.pop() {
+                total += tree.nodes[index as usize].length as usize;
+                stack.extend(tree.nodes[index as usize].children.iter().filter(|c| **c != NIL));
+            }
+            total
+        };
+
+        // Keep about 200 keys alive while 20000 come and go
+        for round in 0..20000 {
+            if live.len() < 200 || rng.random_bool(0.5) {
+                let mut bytes = [0u8; 8];
+                rng.fill_bytes(&mut bytes);
+                let key = bytes.view_bits::<Msb0>()[..rng.random_range(1..64)].to_bitvec();
+                if tree.insert(&key) {
+                    live.push(key);
+                }
+            } else {
+                let key = live.swap_remove(rng.random_range(0..live.len()));
+                assert!(tree.remove(&key));
+            }
+            if round % 100 == 0 {
+                let used = segment_bits(&tree);
+                assert_eq!(tree.bits.len() - tree.garbage, used);
+                assert!(tree.bits.len() <= 2 * used);
+            }
+        }
+        assert_eq!(tree.get_length(), live.len());
+        for key in &live {
+            assert!(tree.search(key));
+        }
+        for key in live.drain(..) {
+            assert!(tree.remove(&key));
+        }
+        assert!(tree.bits.is_empty());
+        assert!(tree.nodes.is_empty());
+    }
+}
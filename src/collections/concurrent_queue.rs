@@ -0,0 +1,203 @@
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use crate::collections::hazard_pointer::{retire, HazardPointer};
+
+// The node `head` points at is a dummy whose value has already been taken (or never existed).
+struct Node<T> {
+    value: MaybeUninit<T>,
+    next: AtomicPtr<Node<T>>,
+}
+
+impl<T> Node<T> {
+    fn new(value: MaybeUninit<T>) -> *mut Self {
+        Box::into_raw(Box::new(Node {
+            value,
+            next: AtomicPtr::new(ptr::null_mut()),
+        }))
+    }
+}
+
+// Michael–Scott queue with hazard pointers for reclaiming dequeued dummy nodes.
+pub struct ConcurrentQueue<T> {
+    head: AtomicPtr<Node<T>>,
+    tail: AtomicPtr<Node<T>>,
+}
+
+unsafe impl<T> Send for ConcurrentQueue<T> where T: Send {}
+unsafe impl<T> Sync for ConcurrentQueue<T> where T: Send {}
+
+impl<T> ConcurrentQueue<T> {
+    pub fn new() -> Self {
+        let dummy = Node::new(MaybeUninit::uninit());
+        ConcurrentQueue {
+            head: AtomicPtr::new(dummy),
+            tail: AtomicPtr::new(dummy),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let hazard = HazardPointer::new();
+        let head = hazard.protect(&self.head);
+        unsafe { (*head).next.load(Ordering::Acquire).is_null() }
+    }
+
+    pub fn enqueue(&self, value: T) {
+        let node = Node::new(MaybeUninit::new(value));
+        let hazard = HazardPointer::new();
+
+        loop {
+            let tail = hazard.protect(&self.tail);
+            let next = unsafe { (*tail).next.load(Ordering::Acquire) };
+            if tail != self.tail.load(Ordering::Acquire) {
+                continue;
+            }
+
+            if next.is_null() {
+                let linked = unsafe { (*tail).next.compare_exchange(ptr::null_mut(), node, Ordering::Release, Ordering::Relaxed) };
+                if linked.is_ok() {
+                    let _ = self.tail.compare_exchange(tail, node, Ordering::Release, Ordering::Relaxed);
+                    return;
+                }
+            } else {
+                // Another enqueue linked its node but has not swung the tail yet; help it along.
+                let _ = self.tail.compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn dequeue(&self) -> Option<T> {
+        let head_hazard = HazardPointer::new();
+        let next_hazard = HazardPointer::new();
+
+        loop {
+            let head = head_hazard.protect(&self.head);
+            let tail = self.tail.load(Ordering::Acquire);
+            let next = next_hazard.protect(unsafe { &(*head).next });
+            if head != self.head.load(Ordering::Acquire) {
+                continue;
+            }
+
+            if next.is_null() {
+                return None;
+            }
+            if head == tail {
+                let _ = self.tail.compare_exchange(tail, next, Ordering::Release, Ordering::Relaxed);
+                continue;
+            }
+
+            if self.head.compare_exchange(head, next, Ordering::AcqRel, Ordering::Relaxed).is_ok() {
+                // `next` is the new dummy; its value is moved out exactly once, by this thread.
+                let value = unsafe { (*next).value.assume_init_read() };
+                head_hazard.reset();
+                next_hazard.reset();
+                unsafe { retire(head) };
+                return Some(value);
+            }
+        }
+    }
+}
+
+impl<T> Drop for ConcurrentQueue<T> {
+    fn drop(&mut self) {
+        let dummy = unsafe { Box::from_raw(*self.head.get_mut()) };
+        let mut iter = dummy.next.load(Ordering::Relaxed);
+        while !iter.is_null() {
+            let mut node = unsafe { Box::from_raw(iter) };
+            unsafe { node.value.assume_init_drop() };
+            iter = node.next.load(Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn enqueue_dequeue() {
+        let queue = ConcurrentQueue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.dequeue(), None);
+        queue.enqueue(1);
+        queue.enqueue(2);
+        assert!(!queue.is_empty());
+        assert_eq!(queue.dequeue(), Some(1));
+        queue.enqueue(3);
+        assert_eq!(queue.dequeue(), Some(2));
+        assert_eq!(queue.dequeue(), Some(3));
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn stress_no_loss_duplication_or_reordering() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 20_000;
+        let queue = ConcurrentQueue::new();
+
+        let dequeued: Vec<Vec<(usize, usize)>> = thread::scope(|scope| {
+            for t in 0..THREADS {
+                let queue = &queue;
+                scope.spawn(move || {
+                    for i in 0..PER_THREAD {
+                        queue.enqueue((t, i));
+                    }
+                });
+            }
+            let consumers: Vec<_> = (0..THREADS).map(|_| {
+                let queue = &queue;
+                scope.spawn(move || {
+                    let mut seen = Vec::new();
+                    for _ in 0..PER_THREAD {
+                        loop {
+                            if let Some(v) = queue.dequeue() {
+                                seen.push(v);
+                                break;
+                            }
+                            thread::yield_now();
+                        }
+                    }
+                    seen
+                })
+            }).collect();
+            consumers.into_iter().map(|c| c.join().unwrap()).collect()
+        });
+
+        let mut all = HashSet::new();
+        for seen in dequeued {
+            // Each consumer must see every producer's values in the order they were enqueued.
+            let mut last = [None; THREADS];
+            for (producer, i) in seen {
+                assert!(last[producer] < Some(i));
+                last[producer] = Some(i);
+                assert!(all.insert((producer, i)), "({}, {}) was dequeued twice", producer, i);
+            }
+        }
+        assert_eq!(all.len(), THREADS * PER_THREAD);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn drops_every_value_once() {
+        let counter = Arc::new(());
+        let queue = ConcurrentQueue::new();
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        queue.enqueue(counter.clone());
+                        queue.enqueue(counter.clone());
+                        drop(queue.dequeue());
+                    }
+                });
+            }
+        });
+        assert_eq!(Arc::strong_count(&counter), 4001);
+        drop(queue);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,162 @@
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use crate::collections::hazard_pointer::{retire, HazardPointer};
+
+struct Node<T> {
+    value: ManuallyDrop<T>,
+    next: *mut Node<T>,
+}
+
+// Treiber stack. Popped nodes are reclaimed through hazard pointers, which also rules out ABA:
+// a node cannot be freed and reused while a popping thread still holds it.
+pub struct ConcurrentStack<T> {
+    head: AtomicPtr<Node<T>>,
+}
+
+unsafe impl<T> Send for ConcurrentStack<T> where T: Send {}
+unsafe impl<T> Sync for ConcurrentStack<T> where T: Send {}
+
+impl<T> ConcurrentStack<T> {
+    pub fn new() -> Self {
+        ConcurrentStack {
+            head: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire).is_null()
+    }
+
+    pub fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(Node {
+            value: ManuallyDrop::new(value),
+            next: ptr::null_mut(),
+        }));
+
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            unsafe { (*node).next = head };
+            match self.head.compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let hazard = HazardPointer::new();
+        loop {
+            let head = hazard.protect(&self.head);
+            if head.is_null() {
+                return None;
+            }
+
+            let next = unsafe { (*head).next };
+            if self.head.compare_exchange(head, next, Ordering::AcqRel, Ordering::Relaxed).is_ok() {
+                // Only the thread whose exchange succeeded takes the value out.
+                let value = unsafe { ManuallyDrop::take(&mut (*head).value) };
+                hazard.reset();
+                unsafe { retire(head) };
+                return Some(value);
+            }
+        }
+    }
+}
+
+impl<T> Drop for ConcurrentStack<T> {
+    fn drop(&mut self) {
+        let mut iter = *self.head.get_mut();
+        while !iter.is_null() {
+            let mut node = unsafe { Box::from_raw(iter) };
+            unsafe { ManuallyDrop::drop(&mut node.value) };
+            iter = node.next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn push_pop() {
+        let stack = ConcurrentStack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+        assert!(!stack.is_empty());
+        assert_eq!(stack.pop(), Some(3));
+        assert_eq!(stack.pop(), Some(2));
+        assert_eq!(stack.pop(), Some(1));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn stress_no_loss_or_duplication() {
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 20_000;
+        let stack = ConcurrentStack::new();
+
+        let popped: Vec<Vec<usize>> = thread::scope(|scope| {
+            for t in 0..THREADS {
+                let stack = &stack;
+                scope.spawn(move || {
+                    for i in 0..PER_THREAD {
+                        stack.push(t * PER_THREAD + i);
+                    }
+                });
+            }
+            let consumers: Vec<_> = (0..THREADS).map(|_| {
+                let stack = &stack;
+                scope.spawn(move || {
+                    let mut seen = Vec::new();
+                    for _ in 0..PER_THREAD {
+                        loop {
+                            if let Some(v) = stack.pop() {
+                                seen.push(v);
+                                break;
+                            }
+                            thread::yield_now();
+                        }
+                    }
+                    seen
+                })
+            }).collect();
+            consumers.into_iter().map(|c| c.join().unwrap()).collect()
+        });
+
+        let mut all = HashSet::new();
+        for v in popped.into_iter().flatten() {
+            assert!(all.insert(v), "{} was popped twice", v);
+        }
+        assert_eq!(all.len(), THREADS * PER_THREAD);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn drops_every_value_once() {
+        let counter = Arc::new(());
+        let stack = ConcurrentStack::new();
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        stack.push(counter.clone());
+                        stack.push(counter.clone());
+                        drop(stack.pop());
+                    }
+                });
+            }
+        });
+        assert_eq!(Arc::strong_count(&counter), 4001);
+        drop(stack);
+        assert_eq!(Arc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::collections::linked_list::QueueStackMix;
+use crate::collections::ring_buffer::RingBuffer;
+
+// Growable ring buffer with an optional limit on how many values it accepts.
+pub struct Deque<T> {
+    ring: RingBuffer<T>,
+    limit: Option<usize>,
+}
+
+impl<T> Deque<T> {
+    pub fn new() -> Self {
+        Deque {
+            ring: RingBuffer::new(),
+            limit: None,
+        }
+    }
+
+    pub fn with_capacity_limit(limit: usize) -> Self {
+        Deque {
+            ring: RingBuffer::new(),
+            limit: Some(limit),
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.ring.get_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.limit.is_some_and(|limit| self.ring.get_length() >= limit)
+    }
+}
+
+impl<T> QueueStackMix<T> for Deque<T> {
+    fn push_to_beginning(&mut self, value: T) -> Result<(), ()> {
+        if self.is_full() {
+            return Err(());
+        }
+        self.ring.push_front(value);
+        Ok(())
+    }
+    fn pop_from_beginning(&mut self) -> Option<T> {
+        self.ring.pop_front()
+    }
+    fn push_to_end(&mut self, value: T) -> Result<(), ()> {
+        if self.is_full() {
+            return Err(());
+        }
+        self.ring.push_back(value);
+        Ok(())
+    }
+    fn pop_from_end(&mut self) -> Option<T> {
+        self.ring.pop_back()
+    }
+    fn peek_beginning(&self) -> Option<&T> {
+        self.ring.peek_beginning()
+    }
+    fn peek_last(&self) -> Option<&T> {
+        self.ring.peek_last()
+    }
+    fn peek_mut_beginning(&mut self) -> Option<&mut T> {
+        self.ring.peek_mut_beginning()
+    }
+    fn peek_mut_last(&mut self) -> Option<&mut T> {
+        self.ring.peek_mut_last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn new_deque() {
+        let mut d: Deque<u32> = Deque::new();
+        assert!(d.is_empty());
+        assert_eq!(d.pop_from_beginning(), None);
+        assert_eq!(d.pop_from_end(), None);
+        assert_eq!(d.peek_beginning(), None);
+        assert_eq!(d.peek_last(), None);
+        assert_eq!(d.peek_mut_beginning(), None);
+        assert_eq!(d.peek_mut_last(), None);
+    }
+
+    #[test]
+    fn mixed_push_pop_end_beginning() {
+        let mut d: Deque<u32> = Deque::new();
+        for i in 0..10 {
+            assert!(d.push_to_end(i).is_ok());
+            assert!(d.push_to_beginning(100 + i).is_ok());
+        }
+        assert_eq!(d.get_length(), 20);
+        assert_eq!(d.peek_beginning(), Some(&109));
+        assert_eq!(d.peek_last(), Some(&9));
+
+        if let Some(v) = d.peek_mut_beginning() {
+            *v = 1;
+        }
+        if let Some(v) = d.peek_mut_last() {
+            *v = 2;
+        }
+        assert_eq!(d.pop_from_beginning(), Some(1));
+        assert_eq!(d.pop_from_end(), Some(2));
+
+        for i in (0..9).rev() {
+            assert_eq!(d.pop_from_beginning(), Some(100 + i));
+            assert_eq!(d.pop_from_end(), Some(i));
+        }
+        assert_eq!(d.get_length(), 0);
+        assert_eq!(d.pop_from_end(), None);
+    }
+
+    #[test]
+    fn wrap_around() {
+        let mut d: Deque<u32> = Deque::new();
+        let mut oracle = std::collections::VecDeque::new();
+        for round in 0..200 {
+            if round % 3 == 0 {
+                assert!(d.push_to_beginning(round).is_ok());
+                oracle.push_front(round);
+            } else {
+                assert!(d.push_to_end(round).is_ok());
+                oracle.push_back(round);
+            }
+            if round % 4 == 0 {
+                assert_eq!(d.pop_from_beginning(), oracle.pop_front());
+            }
+            if round % 5 == 0 {
+                assert_eq!(d.pop_from_end(), oracle.pop_back());
+            }
+            assert_eq!(d.get_length(), oracle.len());
+            assert_eq!(d.peek_beginning(), oracle.front());
+            assert_eq!(d.peek_last(), oracle.back());
+        }
+    }
+
+    #[test]
+    fn capacity_limit() {
+        let mut d: Deque<u32> = Deque::with_capacity_limit(2);
+        assert!(d.push_to_end(1).is_ok());
+        assert!(d.push_to_beginning(0).is_ok());
+        assert!(d.is_full());
+        assert_eq!(d.push_to_end(2), Err(()));
+        assert_eq!(d.push_to_beginning(2), Err(()));
+        assert_eq!(d.pop_from_end(), Some(1));
+        assert!(d.push_to_end(2).is_ok());
+        assert_eq!(d.pop_from_beginning(), Some(0));
+        assert_eq!(d.pop_from_beginning(), Some(2));
+    }
+}
@@ -1,5 +1,6 @@
 use std::cell::RefCell;
-use std::rc::Rc;
+use std::mem::swap;
+use std::rc::{Rc, Weak};
 
 type Link<T> = Option<Rc<RefCell<Node<T>>>>;
 struct Node<T> {
@@ -8,6 +9,16 @@ struct Node<T> {
     previous: Link<T>,
 }
 
+// Handle to a node that stays valid until the node leaves its list. Passing a handle to a list
+// other than the one that created it is a logic error.
+pub struct NodeRef<T>(Weak<RefCell<Node<T>>>);
+
+impl<T> Clone for NodeRef<T> {
+    fn clone(&self) -> Self {
+        NodeRef(self.0.clone())
+    }
+}
+
 pub struct DoublyLinkedList<T> {
     head: Link<T>,
     tail: Link<T>,
@@ -163,23 +174,310 @@ impl<T> DoublyLinkedList<T> {
         result
     }
 
-    fn get_first(&mut self) -> Option<T> {
+    pub fn get_first(&mut self) -> Option<T> {
         self.get_by_index(0)
     }
 
-    fn get_last(&mut self) -> Option<T> {
+    pub fn get_last(&mut self) -> Option<T> {
         self.get_by_index(-1)
     }
 
-    fn insert_first(&mut self, value: T) -> Result<(), ()> {
+    pub fn insert_first(&mut self, value: T) -> Result<(), ()> {
         self.insert_by_index(0, value)
     }
 
-    fn insert_last(&mut self, value: T) -> Result<(), ()> {
+    pub fn insert_last(&mut self, value: T) -> Result<(), ()> {
         self.insert_by_index(-1, value)
     }
 }
 
+impl<T> DoublyLinkedList<T> {
+    fn node_at(&self, index: usize) -> Link<T> {
+        if index >= self.length {
+            return None;
+        }
+
+        if index < self.length / 2 {
+            let mut iter = self.head.clone();
+            for _ in 0..index {
+                iter = iter.unwrap().borrow().next.clone();
+            }
+            iter
+        } else {
+            let mut iter = self.tail.clone();
+            for _ in index + 1..self.length {
+                iter = iter.unwrap().borrow().previous.clone();
+            }
+            iter
+        }
+    }
+
+    // Detaches `node` from its neighbours, leaving it with no links so it can be dropped or relinked.
+    fn unlink(&mut self, node: &Rc<RefCell<Node<T>>>) {
+        let previous = node.borrow_mut().previous.take();
+        let next = node.borrow_mut().next.take();
+
+        match &previous {
+            Some(p) => p.borrow_mut().next = next.clone(),
+            None => self.head = next.clone(),
+        }
+        match &next {
+            Some(n) => n.borrow_mut().previous = previous,
+            None => self.tail = previous,
+        }
+        self.length -= 1;
+    }
+
+    fn link_last(&mut self, node: Rc<RefCell<Node<T>>>) {
+        match self.tail.take() {
+            Some(old_tail) => {
+                node.borrow_mut().previous = Some(old_tail.clone());
+                old_tail.borrow_mut().next = Some(node.clone());
+            }
+            None => self.head = Some(node.clone()),
+        }
+        self.tail = Some(node);
+        self.length += 1;
+    }
+
+    pub fn push_last_node(&mut self, value: T) -> NodeRef<T> {
+        let node = Rc::new(RefCell::new(Node {
+            value,
+            next: None,
+            previous: None,
+        }));
+        let handle = NodeRef(Rc::downgrade(&node));
+        self.link_last(node);
+        handle
+    }
+
+    // Returns None when the node has already been removed.
+    pub fn remove_node(&mut self, handle: &NodeRef<T>) -> Option<T> {
+        let node = handle.0.upgrade()?;
+        self.unlink(&node);
+        Some(Rc::try_unwrap(node).ok().unwrap().into_inner().value)
+    }
+
+    pub fn move_node_to_last(&mut self, handle: &NodeRef<T>) -> Result<(), ()> {
+        let node = handle.0.upgrade().ok_or(())?;
+        self.unlink(&node);
+        self.link_last(node);
+        Ok(())
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        let other_head = match other.head.take() {
+            Some(h) => h,
+            None => return,
+        };
+
+        match self.tail.take() {
+            Some(old_tail) => {
+                other_head.borrow_mut().previous = Some(old_tail.clone());
+                old_tail.borrow_mut().next = Some(other_head);
+            }
+            None => self.head = Some(other_head),
+        }
+        self.tail = other.tail.take();
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    pub fn split_off(&mut self, at: usize) -> Result<Self, ()> {
+        if at > self.length {
+            return Err(());
+        }
+
+        let mut tail = DoublyLinkedList::new();
+        if at == self.length {
+            return Ok(tail);
+        }
+        if at == 0 {
+            swap(self, &mut tail);
+            return Ok(tail);
+        }
+
+        let first = self.node_at(at).unwrap();
+        let last = first.borrow_mut().previous.take().unwrap();
+        last.borrow_mut().next = None;
+
+        tail.head = Some(first);
+        tail.tail = self.tail.replace(last);
+        tail.length = self.length - at;
+        self.length = at;
+        Ok(tail)
+    }
+
+    pub fn reverse(&mut self) {
+        let mut iter = self.head.clone();
+        while let Some(node) = iter {
+            let mut n = node.borrow_mut();
+            let next = n.next.take();
+            n.next = n.previous.take();
+            n.previous = next.clone();
+            iter = next;
+        }
+        swap(&mut self.head, &mut self.tail);
+    }
+
+    pub fn retain<F>(&mut self, mut keep: F) where F: FnMut(&T) -> bool {
+        drop(self.drain_filter(|value| !keep(value)));
+    }
+
+    // Moves every value matching `extract` into a new list, keeping the relative order of both.
+    pub fn drain_filter<F>(&mut self, mut extract: F) -> Self where F: FnMut(&mut T) -> bool {
+        let mut extracted = DoublyLinkedList::new();
+        let mut iter = self.head.clone();
+
+        while let Some(node) = iter {
+            iter = node.borrow().next.clone();
+            if extract(&mut node.borrow_mut().value) {
+                self.unlink(&node);
+                extracted.link_last(node);
+            }
+        }
+
+        extracted
+    }
+
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F) where F: FnMut(&T) -> K, K: PartialEq {
+        let mut previous: Option<K> = None;
+        let mut iter = self.head.clone();
+
+        while let Some(node) = iter {
+            iter = node.borrow().next.clone();
+            let current = key(&node.borrow().value);
+            if previous.as_ref() == Some(&current) {
+                self.unlink(&node);
+            } else {
+                previous = Some(current);
+            }
+        }
+    }
+
+    pub fn position<F>(&self, mut predicate: F) -> Option<usize> where F: FnMut(&T) -> bool {
+        let mut iter = self.head.clone();
+        let mut index = 0;
+
+        while let Some(node) = iter {
+            if predicate(&node.borrow().value) {
+                return Some(index);
+            }
+            iter = node.borrow().next.clone();
+            index += 1;
+        }
+        None
+    }
+}
+
+impl<T> DoublyLinkedList<T> where T: PartialEq {
+    pub fn dedup(&mut self) {
+        let mut iter = self.head.clone();
+
+        while let Some(node) = iter.take() {
+            let next = node.borrow().next.clone();
+            match next {
+                Some(n) if n.borrow().value == node.borrow().value => {
+                    self.unlink(&n);
+                    iter = Some(node);
+                }
+                _ => iter = next,
+            }
+        }
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.position(|v| v == value).is_some()
+    }
+}
+
+// Bottom-up merge sort that relinks the existing nodes, so it is stable and needs no extra allocation.
+impl<T> DoublyLinkedList<T> where T: PartialOrd {
+    pub fn sort(&mut self) {
+        let mut width = 1;
+        while width < self.length {
+            let mut rest = self.head.take();
+            let mut head: Link<T> = None;
+            let mut tail: Link<T> = None;
+
+            while let Some(left) = rest {
+                let right = Self::split_chain(&left, width);
+                rest = match &right {
+                    Some(r) => Self::split_chain(r, width),
+                    None => None,
+                };
+
+                let (merged_head, merged_tail) = Self::merge_chains(Some(left), right);
+                match &tail {
+                    Some(t) => {
+                        merged_head.as_ref().unwrap().borrow_mut().previous = Some(t.clone());
+                        t.borrow_mut().next = merged_head;
+                    }
+                    None => head = merged_head,
+                }
+                tail = merged_tail;
+            }
+
+            self.head = head;
+            self.tail = tail;
+            width *= 2;
+        }
+    }
+
+    pub fn merge_sorted(&mut self, mut other: Self) {
+        other.tail = None;
+        let (head, tail) = Self::merge_chains(self.head.take(), other.head.take());
+        self.head = head;
+        self.tail = tail;
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    // Cuts the chain starting at `start` after `count` nodes and returns whatever followed.
+    fn split_chain(start: &Rc<RefCell<Node<T>>>, count: usize) -> Link<T> {
+        let mut iter = start.clone();
+        for _ in 1..count {
+            let next = iter.borrow().next.clone();
+            match next {
+                Some(n) => iter = n,
+                None => return None,
+            }
+        }
+
+        let rest = iter.borrow_mut().next.take();
+        if let Some(r) = &rest {
+            r.borrow_mut().previous = None;
+        }
+        rest
+    }
+
+    // Ties are taken from `left` to keep the merge stable. Returns the head and tail of the merged chain.
+    fn merge_chains(mut left: Link<T>, mut right: Link<T>) -> (Link<T>, Link<T>) {
+        let mut head: Link<T> = None;
+        let mut tail: Link<T> = None;
+
+        loop {
+            let source = match (&left, &right) {
+                (Some(l), Some(r)) => if r.borrow().value < l.borrow().value { &mut right } else { &mut left },
+                (Some(_), None) => &mut left,
+                (None, Some(_)) => &mut right,
+                (None, None) => break,
+            };
+
+            let node = source.take().unwrap();
+            *source = node.borrow_mut().next.take();
+            node.borrow_mut().previous = tail.clone();
+            match &tail {
+                Some(t) => t.borrow_mut().next = Some(node.clone()),
+                None => head = Some(node.clone()),
+            }
+            tail = Some(node);
+        }
+
+        (head, tail)
+    }
+}
+
 impl<T> Drop for DoublyLinkedList<T> {
     fn drop(&mut self) {
         while self.get_first().is_some() {}
@@ -191,6 +489,7 @@ impl<T> Drop for DoublyLinkedList<T> {
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use rand::Rng;
 
     #[test]
     fn new_list() {
@@ -269,4 +568,159 @@ mod tests {
         assert_eq!(list.get_by_index(-2).unwrap(), 1);
         assert_eq!(list.get_by_index(0).unwrap(), 5);
     }
-}
\ No newline at end of file
+
+    fn from_values(values: &[i32]) -> DoublyLinkedList<i32> {
+        let mut list = DoublyLinkedList::new();
+        for v in values {
+            assert_eq!(list.insert_last(*v), Ok(()));
+        }
+        list
+    }
+
+    fn drain(list: &mut DoublyLinkedList<i32>) -> Vec<i32> {
+        let length = list.get_length();
+        let mut values = Vec::new();
+        while let Some(v) = list.get_first() {
+            values.push(v);
+        }
+        assert_eq!(values.len(), length);
+        values
+    }
+
+    #[test]
+    fn append_split_off() {
+        let mut list = from_values(&[1, 2, 3]);
+        let mut other = from_values(&[4, 5]);
+        list.append(&mut other);
+        assert_eq!(list.get_length(), 5);
+        assert_eq!(other.get_length(), 0);
+        assert!(other.get_last().is_none());
+
+        let mut tail = list.split_off(3).unwrap();
+        assert_eq!(list.get_length(), 3);
+        assert_eq!(tail.get_length(), 2);
+        assert!(list.split_off(4).is_err());
+        assert_eq!(list.get_last(), Some(3));
+        assert_eq!(tail.get_last(), Some(5));
+        assert_eq!(drain(&mut tail), vec![4]);
+
+        let mut head = list.split_off(0).unwrap();
+        assert_eq!(list.get_length(), 0);
+        assert_eq!(drain(&mut head), vec![1, 2]);
+
+        let mut empty = DoublyLinkedList::new();
+        let mut other = from_values(&[7, 8]);
+        empty.append(&mut other);
+        assert_eq!(empty.get_last(), Some(8));
+        assert_eq!(drain(&mut empty), vec![7]);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut list = from_values(&[1, 2, 3, 4]);
+        list.reverse();
+        assert_eq!(list.get_last(), Some(1));
+        assert_eq!(drain(&mut list), vec![4, 3, 2]);
+    }
+
+    #[test]
+    fn retain_drain_filter() {
+        let mut list = from_values(&[1, 2, 3, 4, 5, 6]);
+        list.retain(|v| *v != 1 && *v != 3);
+        assert_eq!(list.get_length(), 4);
+
+        let mut evens = list.drain_filter(|v| *v % 2 == 0);
+        assert_eq!(list.get_length(), 1);
+        assert_eq!(list.get_last(), Some(5));
+        assert_eq!(drain(&mut evens), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn dedup_search() {
+        let mut list = from_values(&[1, 1, 2, 3, 3, 3, 1]);
+        list.dedup();
+        assert!(list.contains(&3));
+        assert!(!list.contains(&4));
+        assert_eq!(list.position(|v| *v == 3), Some(2));
+        assert_eq!(drain(&mut list), vec![1, 2, 3, 1]);
+
+        let mut list = from_values(&[10, 11, 20, 35, 31, 12]);
+        list.dedup_by_key(|v| *v / 10);
+        assert_eq!(list.get_last(), Some(12));
+        assert_eq!(drain(&mut list), vec![10, 20, 35]);
+    }
+
+    // Ordered by the first field only, so the second field tells equal elements apart.
+    #[derive(Debug, Clone, Copy)]
+    struct Keyed(i32, usize);
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn sort() {
+        let mut rng = rand::rng();
+        for size in [0, 1, 2, 3, 7, 8, 9, 100, 257] {
+            let mut expected = Vec::new();
+            let mut list = DoublyLinkedList::new();
+            for i in 0..size {
+                let v = Keyed(rng.random_range(0..10), i);
+                expected.push(v);
+                assert_eq!(list.insert_last(v), Ok(()));
+            }
+            list.sort();
+            expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!(list.get_length(), size);
+
+            // Draining from the back checks the previous links as well.
+            let mut sorted = Vec::new();
+            while let Some(v) = list.get_last() {
+                sorted.push((v.0, v.1));
+            }
+            sorted.reverse();
+            let expected: Vec<(i32, usize)> = expected.iter().map(|k| (k.0, k.1)).collect();
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn merge_sorted() {
+        let mut list = from_values(&[1, 3, 5, 7]);
+        list.merge_sorted(from_values(&[2, 3, 8, 9]));
+        assert_eq!(list.get_length(), 8);
+        assert_eq!(list.get_last(), Some(9));
+        assert_eq!(list.get_by_index(-1), Some(8));
+        assert_eq!(drain(&mut list), vec![1, 2, 3, 3, 5, 7]);
+
+        let mut empty = DoublyLinkedList::new();
+        empty.merge_sorted(from_values(&[4]));
+        assert_eq!(drain(&mut empty), vec![4]);
+    }
+
+    #[test]
+    fn node_handles() {
+        let mut list = from_values(&[1, 2]);
+        let three = list.push_last_node(3);
+        let four = list.push_last_node(4);
+        assert_eq!(list.get_length(), 4);
+
+        assert_eq!(list.move_node_to_last(&three), Ok(()));
+        assert_eq!(list.remove_node(&four), Some(4));
+        assert_eq!(list.remove_node(&four), None);
+        assert_eq!(list.move_node_to_last(&four), Err(()));
+        assert_eq!(list.get_length(), 3);
+
+        assert_eq!(list.get_last(), Some(3));
+        assert_eq!(list.remove_node(&three), None);
+        assert_eq!(drain(&mut list), vec![1, 2]);
+    }
+}
@@ -0,0 +1,186 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ptr;
+use std::sync::atomic::{fence, AtomicBool, AtomicPtr, Ordering};
+use std::sync::Mutex;
+
+// Retired pointers are only scanned once this many have piled up on a thread.
+const SCAN_THRESHOLD: usize = 64;
+
+// One published pointer. Records are never freed; a released record is reused by the next thread
+// that needs one, so the list only grows to the peak number of simultaneous hazard pointers.
+struct HazardRecord {
+    pointer: AtomicPtr<()>,
+    active: AtomicBool,
+    next: *mut HazardRecord,
+}
+
+static RECORDS: AtomicPtr<HazardRecord> = AtomicPtr::new(ptr::null_mut());
+
+// Pointers left behind by threads that exited before they could be freed.
+static ORPHANS: Mutex<Vec<Retired>> = Mutex::new(Vec::new());
+
+struct Retired {
+    pointer: *mut (),
+    reclaim: unsafe fn(*mut ()),
+}
+
+// A retired pointer is exclusively owned by whichever thread ends up freeing it.
+unsafe impl Send for Retired {}
+
+struct RetiredList(Vec<Retired>);
+
+impl Drop for RetiredList {
+    fn drop(&mut self) {
+        scan(&mut self.0);
+        if !self.0.is_empty() {
+            ORPHANS.lock().unwrap().append(&mut self.0);
+        }
+    }
+}
+
+thread_local! {
+    static RETIRED: RefCell<RetiredList> = const { RefCell::new(RetiredList(Vec::new())) };
+}
+
+// While a pointer is protected by a live hazard pointer, `retire` will not free it.
+pub struct HazardPointer {
+    record: &'static HazardRecord,
+}
+
+impl HazardPointer {
+    pub fn new() -> Self {
+        let mut iter = RECORDS.load(Ordering::Acquire);
+        while let Some(record) = unsafe { iter.as_ref() } {
+            if record.active.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                return HazardPointer { record };
+            }
+            iter = record.next;
+        }
+
+        let record = Box::leak(Box::new(HazardRecord {
+            pointer: AtomicPtr::new(ptr::null_mut()),
+            active: AtomicBool::new(true),
+            next: ptr::null_mut(),
+        }));
+        let mut head = RECORDS.load(Ordering::Acquire);
+        loop {
+            record.next = head;
+            match RECORDS.compare_exchange_weak(head, record, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => return HazardPointer { record },
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    // Loads `source` and publishes it, retrying until the published value is still current. The
+    // result can then be dereferenced until the hazard pointer is reset or dropped.
+    pub fn protect<T>(&self, source: &AtomicPtr<T>) -> *mut T {
+        let mut pointer = source.load(Ordering::Acquire);
+        loop {
+            self.record.pointer.store(pointer as *mut (), Ordering::SeqCst);
+            let current = source.load(Ordering::SeqCst);
+            if current == pointer {
+                return pointer;
+            }
+            pointer = current;
+        }
+    }
+
+    pub fn reset(&self) {
+        self.record.pointer.store(ptr::null_mut(), Ordering::Release);
+    }
+}
+
+impl Drop for HazardPointer {
+    fn drop(&mut self) {
+        self.reset();
+        self.record.active.store(false, Ordering::Release);
+    }
+}
+
+// Defers freeing `pointer` until no hazard pointer protects it.
+//
+// Safety: `pointer` must come from `Box::into_raw`, must already be unreachable for threads that
+// have not protected it yet, and must not be retired twice.
+pub unsafe fn retire<T>(pointer: *mut T) {
+    unsafe fn reclaim<T>(pointer: *mut ()) {
+        drop(unsafe { Box::from_raw(pointer as *mut T) });
+    }
+
+    let retired = Retired {
+        pointer: pointer as *mut (),
+        reclaim: reclaim::<T>,
+    };
+    RETIRED.with(|list| {
+        let list = &mut list.borrow_mut().0;
+        list.push(retired);
+        if list.len() >= SCAN_THRESHOLD {
+            scan(list);
+        }
+    });
+}
+
+// Frees every retired pointer that no hazard pointer currently publishes.
+fn scan(retired: &mut Vec<Retired>) {
+    if let Ok(mut orphans) = ORPHANS.try_lock() {
+        retired.append(&mut orphans);
+    }
+
+    fence(Ordering::SeqCst);
+    let mut protected = HashSet::new();
+    let mut iter = RECORDS.load(Ordering::Acquire);
+    while let Some(record) = unsafe { iter.as_ref() } {
+        let pointer = record.pointer.load(Ordering::SeqCst);
+        if !pointer.is_null() {
+            protected.insert(pointer);
+        }
+        iter = record.next;
+    }
+
+    retired.retain(|r| {
+        if protected.contains(&r.pointer) {
+            return true;
+        }
+        unsafe { (r.reclaim)(r.pointer) };
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    static PROTECTED_DROPPED: AtomicBool = AtomicBool::new(false);
+
+    // Not zero-sized, so its box gets an address of its own.
+    struct Protected(u64);
+
+    impl Drop for Protected {
+        fn drop(&mut self) {
+            PROTECTED_DROPPED.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn protected_pointers_survive_scans() {
+        let protected = Box::into_raw(Box::new(Protected(7)));
+        let source = AtomicPtr::new(protected);
+        let hazard = HazardPointer::new();
+        assert_eq!(hazard.protect(&source), protected);
+        assert_eq!(unsafe { (*protected).0 }, 7);
+
+        unsafe { retire(protected) };
+        for i in 0..2 * SCAN_THRESHOLD {
+            unsafe { retire(Box::into_raw(Box::new(i))) };
+        }
+        assert!(!PROTECTED_DROPPED.load(Ordering::SeqCst));
+
+        drop(hazard);
+        for i in 0..2 * SCAN_THRESHOLD {
+            unsafe { retire(Box::into_raw(Box::new(i))) };
+        }
+        assert!(PROTECTED_DROPPED.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,234 @@
+use bitvec::prelude::*;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use crate::collections::loopy_patricia::PatriciaMap;
+
+// An address prefix such as 10.0.0.0/8 or 2001:db8::/32. Host bits past the prefix length are
+// cleared when parsing, and a plain address is a full-length prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpPrefix {
+    address: IpAddr,
+    length: u8,
+}
+
+impl IpPrefix {
+    pub fn new(address: IpAddr, length: u8) -> Result<Self, ()> {
+        let bits = address_bits(&address);
+        if length as usize > bits.len() {
+            return Err(());
+        }
+        Ok(Self::from_bits(address.is_ipv6(), &bits[..length as usize]))
+    }
+
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+
+    fn key(&self) -> BitVec<u8, Msb0> {
+        address_bits(&self.address)[..self.length as usize].to_bitvec()
+    }
+
+    fn from_bits(is_ipv6: bool, prefix: &BitSlice<u8, Msb0>) -> Self {
+        let address = if is_ipv6 {
+            let mut octets = [0u8; 16];
+            octets.view_bits_mut::<Msb0>()[..prefix.len()].copy_from_bitslice(prefix);
+            IpAddr::V6(Ipv6Addr::from(octets))
+        } else {
+            let mut octets = [0u8; 4];
+            octets.view_bits_mut::<Msb0>()[..prefix.len()].copy_from_bitslice(prefix);
+            IpAddr::V4(Ipv4Addr::from(octets))
+        };
+        IpPrefix {
+            address,
+            length: prefix.len() as u8,
+        }
+    }
+}
+
+fn address_bits(address: &IpAddr) -> BitVec<u8, Msb0> {
+    match address {
+        IpAddr::V4(v4) => v4.octets().view_bits::<Msb0>().to_bitvec(),
+        IpAddr::V6(v6) => v6.octets().view_bits::<Msb0>().to_bitvec(),
+    }
+}
+
+impl FromStr for IpPrefix {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, ()> {
+        let (address, length) = match text.split_once('/') {
+            Some((address, length)) => (address, Some(length)),
+            None => (text, None),
+        };
+        let address: IpAddr = address.parse().map_err(|_| ())?;
+        let length = match length {
+            Some(length) => length.parse().map_err(|_| ())?,
+            None => if address.is_ipv6() { 128 } else { 32 },
+        };
+        IpPrefix::new(address, length)
+    }
+}
+
+impl fmt::Display for IpPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.length)
+    }
+}
+
+// Routes for both address families, each kept in its own Patricia map keyed by prefix bits.
+pub struct IpRoutingTable<V> {
+    v4: PatriciaMap<V>,
+    v6: PatriciaMap<V>,
+}
+
+impl<V> IpRoutingTable<V> {
+    pub fn new() -> Self {
+        IpRoutingTable {
+            v4: PatriciaMap::new(),
+            v6: PatriciaMap::new(),
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.v4.get_length() + self.v6.get_length()
+    }
+
+    fn family(&self, is_ipv6: bool) -> &PatriciaMap<V> {
+        if is_ipv6 { &self.v6 } else { &self.v4 }
+    }
+
+    fn family_mut(&mut self, is_ipv6: bool) -> &mut PatriciaMap<V> {
+        if is_ipv6 { &mut self.v6 } else { &mut self.v4 }
+    }
+
+    // Adds a route such as "192.168.0.0/16", returning the one it replaced. Fails on bad input.
+    pub fn insert(&mut self, prefix: &str, value: V) -> Result<Option<V>, ()> {
+        let prefix: IpPrefix = prefix.parse()?;
+        Ok(self.insert_prefix(prefix, value))
+    }
+
+    pub fn insert_prefix(&mut self, prefix: IpPrefix, value: V) -> Option<V> {
+        self.family_mut(prefix.address.is_ipv6()).insert(&prefix.key(), value)
+    }
+
+    pub fn remove(&mut self, prefix: &str) -> Result<Option<V>, ()> {
+        let prefix: IpPrefix = prefix.parse()?;
+        Ok(self.family_mut(prefix.address.is_ipv6()).remove(&prefix.key()))
+    }
+
+    pub fn get(&self, prefix: &IpPrefix) -> Option<&V> {
+        self.family(prefix.address.is_ipv6()).get(&prefix.key())
+    }
+
+    // The most specific route covering the address
+    pub fn lookup(&self, address: IpAddr) -> Option<(IpPrefix, &V)> {
+        let bits = address_bits(&address);
+        let (prefix, value) = self.family(address.is_ipv6()).longest_prefix_match(&bits)?;
+        Some((IpPrefix::from_bits(address.is_ipv6(), &prefix), value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn parse_prefixes() {
+        let prefix: IpPrefix = "10.1.2.3/8".parse().unwrap();
+        assert_eq!(prefix.to_string(), "10.0.0.0/8");
+        assert_eq!("192.168.1.1".parse::<IpPrefix>().unwrap().to_string(), "192.168.1.1/32");
+        assert_eq!("2001:db8:ffff::1/32".parse::<IpPrefix>().unwrap().to_string(), "2001:db8::/32");
+        assert_eq!("0.0.0.0/0".parse::<IpPrefix>().unwrap().length(), 0);
+        assert_eq!("10.0.0.0/33".parse::<IpPrefix>(), Err(()));
+        assert_eq!("10.0.0/8".parse::<IpPrefix>(), Err(()));
+        assert_eq!("::/x".parse::<IpPrefix>(), Err(()));
+    }
+
+    #[test]
+    fn routes() {
+        let mut table = IpRoutingTable::new();
+        assert_eq!(table.insert("0.0.0.0/0", "default"), Ok(None));
+        assert_eq!(table.insert("10.0.0.0/8", "corp"), Ok(None));
+        assert_eq!(table.insert("10.20.0.0/16", "lab"), Ok(None));
+        assert_eq!(table.insert("2001:db8::/32", "v6"), Ok(None));
+        assert_eq!(table.insert("10.20.0.0/16", "lab2"), Ok(Some("lab")));
+        assert_eq!(table.insert("10.0.0.0/40", "bad"), Err(()));
+        assert_eq!(table.get_length(), 4);
+
+        let lookup = |table: &IpRoutingTable<&'static str>, address: &str| {
+            table.lookup(address.parse().unwrap()).map(|(prefix, value)| (prefix.to_string(), *value))
+        };
+        assert_eq!(lookup(&table, "10.20.30.40"), Some(("10.20.0.0/16".to_string(), "lab2")));
+        assert_eq!(lookup(&table, "10.21.0.1"), Some(("10.0.0.0/8".to_string(), "corp")));
+        assert_eq!(lookup(&table, "8.8.8.8"), Some(("0.0.0.0/0".to_string(), "default")));
+        assert_eq!(lookup(&table, "2001:db8::1"), Some(("2001:db8::/32".to_string(), "v6")));
+        assert_eq!(lookup(&table, "2001:db9::1"), None);
+
+        assert_eq!(table.remove("10.0.0.0/8"), Ok(Some("corp")));
+        assert_eq!(lookup(&table, "10.21.0.1"), Some(("0.0.0.0/0".to_string(), "default")));
+        assert_eq!(table.get(&"10.20.0.0/16".parse().unwrap()), Some(&"lab2"));
+    }
+
+    // Checks the table against a linear scan over every route, with addresses drawn from a small
+    // neighbourhood so that nested prefixes are common.
+    #[test]
+    fn random_lookups_against_brute_force() {
+        let mut rng = rand::rng();
+        let mut table = IpRoutingTable::new();
+        let mut routes: Vec<(u32, u8, usize)> = Vec::new();
+        let mut routes_v6: Vec<(u128, u8, usize)> = Vec::new();
+        let v4_address = |rng: &mut rand::rngs::ThreadRng| 0x0a00_0000u32 | rng.random_range(0..1 << 12) << 12;
+        let v6_address = |rng: &mut rand::rngs::ThreadRng| (0x2001_0db8u128 << 96) | (rng.random_range(0..256u128) << 88);
+
+        for i in 0..300 {
+            let length = rng.random_range(0..=32);
+            let network = v4_address(&mut rng) & u32::MAX.checked_shl(32 - length as u32).unwrap_or(0);
+            let prefix = format!("{}/{}", Ipv4Addr::from(network), length);
+            if table.insert(&prefix, i).unwrap().is_none() {
+                routes.push((network, length, i));
+            } else {
+                routes.iter_mut().find(|r| r.0 == network && r.1 == length).unwrap().2 = i;
+            }
+
+            let length = rng.random_range(0..=128);
+            let network = v6_address(&mut rng) & u128::MAX.checked_shl(128 - length as u32).unwrap_or(0);
+            let prefix = format!("{}/{}", Ipv6Addr::from(network), length);
+            if table.insert(&prefix, i).unwrap().is_none() {
+                routes_v6.push((network, length, i));
+            } else {
+                routes_v6.iter_mut().find(|r| r.0 == network && r.1 == length).unwrap().2 = i;
+            }
+        }
+
+        for _ in 0..2000 {
+            let address = v4_address(&mut rng) | rng.random_range(0..1 << 12);
+            let expected = routes
+                .iter()
+                .filter(|(network, length, _)| {
+                    (address ^ network).checked_shr(32 - *length as u32).unwrap_or(0) == 0
+                })
+                .max_by_key(|(_, length, _)| *length)
+                .map(|(_, length, value)| (*length, *value));
+            let found = table.lookup(IpAddr::V4(Ipv4Addr::from(address)));
+            assert_eq!(found.map(|(prefix, value)| (prefix.length(), *value)), expected);
+
+            let address = v6_address(&mut rng) | rng.random_range(0..1u128 << 88);
+            let expected = routes_v6
+                .iter()
+                .filter(|(network, length, _)| {
+                    (address ^ network).checked_shr(128 - *length as u32).unwrap_or(0) == 0
+                })
+                .max_by_key(|(_, length, _)| *length)
+                .map(|(_, length, value)| (*length, *value));
+            let found = table.lookup(IpAddr::V6(Ipv6Addr::from(address)));
+            assert_eq!(found.map(|(prefix, value)| (prefix.length(), *value)), expected);
+        }
+    }
+}
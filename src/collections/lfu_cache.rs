@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::collections::doubly_linked_list::{DoublyLinkedList, NodeRef};
+use crate::collections::lru_cache::{CacheStats, EvictionCallback, Weigher};
+
+struct LfuEntry<K, V> {
+    value: V,
+    frequency: usize,
+    node: NodeRef<K>,
+}
+
+struct LfuBucket<K> {
+    keys: DoublyLinkedList<K>,
+    lower: Option<usize>,
+    higher: Option<usize>,
+}
+
+// The frequencies in use, linked in increasing order so the lowest is always known and a key
+// moving up one step finds its next bucket right above the old one.
+struct LfuBuckets<K> {
+    buckets: HashMap<usize, LfuBucket<K>>,
+    lowest: Option<usize>,
+}
+
+impl<K> LfuBuckets<K> {
+    // Adds `key` to the bucket for `frequency`, which goes just above `lower` if it is new.
+    fn push(&mut self, frequency: usize, lower: Option<usize>, key: K) -> NodeRef<K> {
+        if !self.buckets.contains_key(&frequency) {
+            let higher = match lower {
+                Some(lower) => self.buckets.get_mut(&lower).unwrap().higher.replace(frequency),
+                None => self.lowest.replace(frequency),
+            };
+            if let Some(higher) = higher {
+                self.buckets.get_mut(&higher).unwrap().lower = Some(frequency);
+            }
+            self.buckets.insert(frequency, LfuBucket { keys: DoublyLinkedList::new(), lower, higher });
+        }
+        self.buckets.get_mut(&frequency).unwrap().keys.push_last_node(key)
+    }
+
+    // Moves a key up to the next frequency. Its old bucket has to stay until the new one is
+    // linked in above it.
+    fn bump(&mut self, frequency: usize, node: &NodeRef<K>) -> NodeRef<K> {
+        let key = self.buckets.get_mut(&frequency).unwrap().keys.remove_node(node).unwrap();
+        let node = self.push(frequency + 1, Some(frequency), key);
+        self.drop_if_empty(frequency);
+        node
+    }
+
+    fn move_to_last(&mut self, frequency: usize, node: &NodeRef<K>) {
+        self.buckets.get_mut(&frequency).unwrap().keys.move_node_to_last(node).unwrap();
+    }
+
+    fn remove(&mut self, frequency: usize, node: &NodeRef<K>) -> K {
+        let key = self.buckets.get_mut(&frequency).unwrap().keys.remove_node(node).unwrap();
+        self.drop_if_empty(frequency);
+        key
+    }
+
+    // Takes the least recently used key of the lowest frequency. A `keep` bucket holding a
+    // single key is passed over.
+    fn pop_lowest(&mut self, keep: Option<usize>) -> K {
+        let mut frequency = self.lowest.unwrap();
+        let bucket = &self.buckets[&frequency];
+        if Some(frequency) == keep && bucket.keys.get_length() == 1 {
+            frequency = bucket.higher.unwrap();
+        }
+        let key = self.buckets.get_mut(&frequency).unwrap().keys.get_first().unwrap();
+        self.drop_if_empty(frequency);
+        key
+    }
+
+    fn drop_if_empty(&mut self, frequency: usize) {
+        if self.buckets[&frequency].keys.get_length() != 0 {
+            return;
+        }
+        let bucket = self.buckets.remove(&frequency).unwrap();
+        match bucket.lower {
+            Some(lower) => self.buckets.get_mut(&lower).unwrap().higher = bucket.higher,
+            None => self.lowest = bucket.higher,
+        }
+        if let Some(higher) = bucket.higher {
+            self.buckets.get_mut(&higher).unwrap().lower = bucket.lower;
+        }
+    }
+}
+
+// Least-frequently-used cache. Keys with the same access count share a bucket ordered from least
+// to most recently used, which breaks ties between equally frequent entries.
+pub struct LfuCache<K, V> {
+    map: HashMap<K, LfuEntry<K, V>>,
+    buckets: LfuBuckets<K>,
+    capacity: usize,
+    weight: usize,
+    weigher: Weigher<K, V>,
+    on_evict: Option<EvictionCallback<K, V>>,
+    stats: CacheStats,
+}
+
+impl<K, V> LfuCache<K, V> where K: Hash + Eq + Clone {
+    // Holds at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_weigher(capacity, Box::new(|_, _| 1))
+    }
+
+    // Holds entries until their total weight would exceed `capacity`.
+    pub fn with_weigher(capacity: usize, weigher: Weigher<K, V>) -> Self {
+        LfuCache {
+            map: HashMap::new(),
+            buckets: LfuBuckets { buckets: HashMap::new(), lowest: None },
+            capacity,
+            weight: 0,
+            weigher,
+            on_evict: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn set_eviction_callback(&mut self, callback: EvictionCallback<K, V>) {
+        self.on_evict = Some(callback);
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn get_weight(&self) -> usize {
+        self.weight
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn frequency(&self, key: &K) -> Option<usize> {
+        self.map.get(key).map(|entry| entry.frequency)
+    }
+
+    // Looks a value up without touching its frequency or the statistics.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.map.get(key).map(|entry| &entry.value)
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let entry = match self.map.get_mut(key) {
+            Some(entry) => entry,
+            None => {
+                self.stats.misses += 1;
+                return None;
+            }
+        };
+        self.stats.hits += 1;
+
+        entry.node = self.buckets.bump(entry.frequency, &entry.node);
+        entry.frequency += 1;
+        Some(&mut entry.value)
+    }
+
+    // Returns the previous value for `key`, whose access count carries over to the new value.
+    // Entries heavier than the whole capacity are handed straight to the eviction callback.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let weight = (self.weigher)(&key, &value);
+        if weight > self.capacity {
+            let old = self.remove(&key);
+            self.evicted(key, value);
+            return old;
+        }
+
+        // The key being written counts as the most recent at its frequency and is never the one
+        // that makes room for itself
+        let (keep, current) = match self.map.get(&key) {
+            Some(entry) => {
+                self.buckets.move_to_last(entry.frequency, &entry.node);
+                (Some(entry.frequency), (self.weigher)(&key, &entry.value))
+            }
+            None => (None, 0),
+        };
+        while self.weight - current + weight > self.capacity {
+            self.evict(keep);
+        }
+        self.weight = self.weight - current + weight;
+
+        match self.map.get_mut(&key) {
+            Some(entry) => Some(std::mem::replace(&mut entry.value, value)),
+            None => {
+                let node = self.buckets.push(1, None, key.clone());
+                self.map.insert(key, LfuEntry { value, frequency: 1, node });
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.map.remove(key)?;
+        let key = self.buckets.remove(entry.frequency, &entry.node);
+        self.weight -= (self.weigher)(&key, &entry.value);
+        Some(entry.value)
+    }
+
+    fn evict(&mut self, keep: Option<usize>) {
+        let key = self.buckets.pop_lowest(keep);
+        let entry = self.map.remove(&key).unwrap();
+        self.weight -= (self.weigher)(&key, &entry.value);
+        self.evicted(key, entry.value);
+    }
+
+    fn evicted(&mut self, key: K, value: V) {
+        self.stats.evictions += 1;
+        if let Some(callback) = self.on_evict.as_mut() {
+            callback(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn evicts_least_frequently_used() {
+        let mut cache = LfuCache::new(2);
+        cache.put(1, "one");
+        cache.put(2, "two");
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.frequency(&1), Some(3));
+
+        cache.put(3, "three");
+        assert!(!cache.contains(&2));
+        assert_eq!(cache.get(&2), None);
+
+        // 3 is now the only entry used once, so it goes before 1.
+        cache.put(4, "four");
+        assert!(!cache.contains(&3));
+        assert!(cache.contains(&1));
+        assert_eq!(cache.get_length(), 2);
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 1, evictions: 2 });
+    }
+
+    #[test]
+    fn ties_evict_least_recently_used() {
+        let mut cache = LfuCache::new(3);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+        cache.get(&"a");
+        cache.get(&"b");
+
+        cache.put("d", 4);
+        assert!(!cache.contains(&"c"));
+        cache.get(&"d");
+
+        cache.put("e", 5);
+        assert!(!cache.contains(&"a"));
+        assert!(cache.contains(&"b"));
+        assert!(cache.contains(&"d"));
+    }
+
+    #[test]
+    fn update_and_remove() {
+        let mut cache = LfuCache::new(2);
+        cache.put(1, 10);
+        cache.get(&1);
+        assert_eq!(cache.put(1, 11), Some(10));
+        assert_eq!(cache.frequency(&1), Some(2));
+
+        cache.put(2, 20);
+        assert_eq!(cache.remove(&2), Some(20));
+        assert_eq!(cache.remove(&2), None);
+
+        // Removing the only entry at the lowest frequency must not confuse the next eviction.
+        cache.put(3, 30);
+        cache.put(4, 40);
+        assert!(cache.contains(&1));
+        assert!(!cache.contains(&3));
+        assert_eq!(cache.peek(&4), Some(&40));
+    }
+
+    #[test]
+    fn weighted_capacity_and_callback() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let mut cache: LfuCache<&str, String> = LfuCache::with_weigher(10, Box::new(|_, v| v.len()));
+        let log = evicted.clone();
+        cache.set_eviction_callback(Box::new(move |k, _| log.borrow_mut().push(k)));
+
+        cache.put("a", "xxxx".to_string());
+        cache.put("b", "xxxx".to_string());
+        cache.get(&"b");
+        cache.put("c", "xxxxxxxx".to_string());
+        assert_eq!(*evicted.borrow(), vec!["a", "b"]);
+        assert_eq!(cache.get_weight(), 8);
+
+        cache.put("huge", "x".repeat(11));
+        assert_eq!(*evicted.borrow(), vec!["a", "b", "huge"]);
+        assert_eq!(cache.get_length(), 1);
+    }
+
+    #[test]
+    fn weighted_update_evicts_other_keys() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let mut cache: LfuCache<&str, String> = LfuCache::with_weigher(10, Box::new(|_, v| v.len()));
+        let log = evicted.clone();
+        cache.set_eviction_callback(Box::new(move |k, _| log.borrow_mut().push(k)));
+
+        cache.put("cold", "xxx".to_string());
+        cache.put("hot", "xxxx".to_string());
+        cache.get(&"hot");
+        cache.get(&"hot");
+        // "cold" has the lowest count, but it is the key being written, so "hot" has to go
+        assert_eq!(cache.put("cold", "xxxxxxxx".to_string()), Some("xxx".to_string()));
+        assert_eq!(*evicted.borrow(), vec!["hot"]);
+        assert_eq!(cache.peek(&"cold"), Some(&"xxxxxxxx".to_string()));
+        assert_eq!(cache.frequency(&"cold"), Some(1));
+        assert_eq!(cache.get_weight(), 8);
+
+        cache.put("new", "xx".to_string());
+        cache.get(&"new");
+        assert_eq!(cache.put("cold", "x".to_string()), Some("xxxxxxxx".to_string()));
+        assert_eq!(cache.get_weight(), 3);
+        cache.put("other", "xxxxxxxx".to_string());
+        assert_eq!(*evicted.borrow(), vec!["hot", "cold"]);
+    }
+
+
+    #[test]
+    fn random_operations_against_model() {
+        let mut rng = rand::rng();
+        let mut cache = LfuCache::new(8);
+        // Value, frequency and the time the key last joined its bucket
+        let mut model: HashMap<u32, (u32, usize, usize)> = HashMap::new();
+        for time in 0..5000 {
+            let key = rng.random_range(0..16);
+            match rng.random_range(0..3) {
+                0 => {
+                    let expected = model.get_mut(&key).map(|entry| {
+                        entry.1 += 1;
+                        entry.2 = time;
+                        entry.0
+                    });
+                    assert_eq!(cache.get(&key).copied(), expected);
+                }
+                1 => {
+                    let value = rng.random();
+                    let old = match model.get_mut(&key) {
+                        Some(entry) => {
+                            entry.2 = time;
+                            Some(std::mem::replace(&mut entry.0, value))
+                        }
+                        None => {
+                            if model.len() == 8 {
+                                let (&victim, _) = model.iter().min_by_key(|(_, entry)| (entry.1, entry.2)).unwrap();
+                                model.remove(&victim);
+                            }
+                            model.insert(key, (value, 1, time));
+                            None
+                        }
+                    };
+                    assert_eq!(cache.put(key, value), old);
+                }
+                _ => assert_eq!(cache.remove(&key), model.remove(&key).map(|entry| entry.0)),
+            }
+            assert_eq!(cache.get_length(), model.len());
+            for (key, entry) in &model {
+                assert_eq!(cache.frequency(key), Some(entry.1));
+            }
+        }
+    }
+}
@@ -114,6 +114,166 @@ impl<T> List<T> {
     }
 }
 
+impl<T> List<T> {
+    pub fn append(&mut self, other: &mut Self) {
+        let mut link = &mut self.head;
+        while let Some(node) = link {
+            link = &mut node.next;
+        }
+        *link = other.head.take();
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    pub fn split_off(&mut self, at: usize) -> Result<Self, ()> {
+        if at > self.length {
+            return Err(());
+        }
+
+        let mut link = &mut self.head;
+        for _ in 0..at {
+            link = &mut link.as_mut().unwrap().next;
+        }
+
+        let tail = List {
+            head: link.take(),
+            length: self.length - at,
+        };
+        self.length = at;
+        Ok(tail)
+    }
+
+    pub fn reverse(&mut self) {
+        let mut rest = self.head.take();
+        while let Some(mut node) = rest {
+            rest = node.next.take();
+            node.next = self.head.take();
+            self.head = Some(node);
+        }
+    }
+
+    pub fn retain<F>(&mut self, mut keep: F) where F: FnMut(&T) -> bool {
+        drop(self.drain_filter(|value| !keep(value)));
+    }
+
+    // Moves every value matching `extract` into a new list, keeping the relative order of both.
+    pub fn drain_filter<F>(&mut self, mut extract: F) -> Self where F: FnMut(&mut T) -> bool {
+        let mut extracted = List::new();
+        let mut extracted_tail = &mut extracted.head;
+        let mut rest = self.head.take();
+        let mut tail = &mut self.head;
+
+        while let Some(mut node) = rest {
+            rest = node.next.take();
+            if extract(&mut node.value) {
+                extracted_tail = &mut extracted_tail.insert(node).next;
+                extracted.length += 1;
+            } else {
+                tail = &mut tail.insert(node).next;
+            }
+        }
+
+        self.length -= extracted.length;
+        extracted
+    }
+
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F) where F: FnMut(&T) -> K, K: PartialEq {
+        let mut previous: Option<K> = None;
+        let mut rest = self.head.take();
+        let mut tail = &mut self.head;
+
+        while let Some(mut node) = rest {
+            rest = node.next.take();
+            let current = key(&node.value);
+            if previous.as_ref() == Some(&current) {
+                self.length -= 1;
+                continue;
+            }
+            previous = Some(current);
+            tail = &mut tail.insert(node).next;
+        }
+    }
+
+    pub fn position<F>(&self, predicate: F) -> Option<usize> where F: FnMut(&T) -> bool {
+        self.iter().position(predicate)
+    }
+}
+
+impl<T> List<T> where T: PartialEq {
+    pub fn dedup(&mut self) {
+        let mut link = self.head.as_mut();
+        while let Some(node) = link {
+            while node.next.as_ref().is_some_and(|next| next.value == node.value) {
+                let mut removed = node.next.take().unwrap();
+                node.next = removed.next.take();
+                self.length -= 1;
+            }
+            link = node.next.as_mut();
+        }
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.iter().any(|v| v == value)
+    }
+}
+
+// Bottom-up merge sort that relinks the existing nodes, so it is stable and needs no extra allocation.
+impl<T> List<T> where T: PartialOrd {
+    pub fn sort(&mut self) {
+        let mut width = 1;
+        while width < self.length {
+            let mut rest = self.head.take();
+            let mut tail = &mut self.head;
+
+            while rest.is_some() {
+                let mut left = rest;
+                let mut right = Self::split_chain(&mut left, width);
+                rest = Self::split_chain(&mut right, width);
+
+                *tail = Self::merge_chains(left, right);
+                while let Some(node) = tail {
+                    tail = &mut node.next;
+                }
+            }
+            width *= 2;
+        }
+    }
+
+    pub fn merge_sorted(&mut self, mut other: Self) {
+        self.head = Self::merge_chains(self.head.take(), other.head.take());
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    // Cuts `chain` after `count` nodes and returns whatever followed.
+    fn split_chain(chain: &mut Option<Box<Node<T>>>, count: usize) -> Option<Box<Node<T>>> {
+        let mut link = chain;
+        for _ in 0..count {
+            match link {
+                Some(node) => link = &mut node.next,
+                None => return None,
+            }
+        }
+        link.take()
+    }
+
+    // Ties are taken from `left` to keep the merge stable.
+    fn merge_chains(mut left: Option<Box<Node<T>>>, mut right: Option<Box<Node<T>>>) -> Option<Box<Node<T>>> {
+        let mut head = None;
+        let mut tail = &mut head;
+
+        while let (Some(l), Some(r)) = (&left, &right) {
+            let source = if r.value < l.value { &mut right } else { &mut left };
+            let mut node = source.take().unwrap();
+            *source = node.next.take();
+            tail = &mut tail.insert(node).next;
+        }
+
+        *tail = if left.is_some() { left } else { right };
+        head
+    }
+}
+
 pub trait QueueStackMix<T> {
     fn push_to_beginning(&mut self, value: T) -> Result<(), ()>;
     fn pop_from_beginning(&mut self) -> Option<T>;
@@ -171,9 +331,12 @@ impl<T> Drop for List<T> {
     }
 }
 
-struct IntoIter<T>(List<T>);
+pub struct IntoIter<T>(List<T>);
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
 
-impl<T> List<T> {
     fn into_iter(self) -> IntoIter<T> {
         IntoIter(self)
     }
@@ -233,6 +396,7 @@ impl<'a, T> Iterator for IterMut<'a, T> {
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use rand::Rng;
 
     #[test]
     fn new_list() {
@@ -429,5 +593,134 @@ mod tests {
         assert_eq!(l.get_by_index(0), Some(7));
         assert_eq!(l.get_length(), 0);
     }
-}
 
+    fn from_values(values: &[u32]) -> List<u32> {
+        let mut l = List::new();
+        for v in values {
+            assert!(l.push_to_end(*v).is_ok());
+        }
+        l
+    }
+
+    fn values(l: &List<u32>) -> Vec<u32> {
+        l.iter().cloned().collect()
+    }
+
+    #[test]
+    fn append_split_off() {
+        let mut l = from_values(&[1, 2, 3]);
+        let mut other = from_values(&[4, 5]);
+        l.append(&mut other);
+        assert_eq!(values(&l), vec![1, 2, 3, 4, 5]);
+        assert_eq!(l.get_length(), 5);
+        assert_eq!(other.get_length(), 0);
+        assert_eq!(other.peek_beginning(), None);
+
+        let tail = l.split_off(2).unwrap();
+        assert_eq!(values(&l), vec![1, 2]);
+        assert_eq!(values(&tail), vec![3, 4, 5]);
+        assert_eq!(l.get_length(), 2);
+        assert_eq!(tail.get_length(), 3);
+        assert!(l.split_off(3).is_err());
+
+        let everything = l.split_off(0).unwrap();
+        assert_eq!(l.get_length(), 0);
+        assert_eq!(values(&everything), vec![1, 2]);
+        assert_eq!(l.pop_from_end(), None);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut l = from_values(&[1, 2, 3, 4]);
+        l.reverse();
+        assert_eq!(values(&l), vec![4, 3, 2, 1]);
+        assert_eq!(l.peek_last(), Some(&1));
+
+        let mut empty: List<u32> = List::new();
+        empty.reverse();
+        assert_eq!(empty.get_length(), 0);
+    }
+
+    #[test]
+    fn retain_drain_filter() {
+        let mut l = from_values(&[1, 2, 3, 4, 5, 6]);
+        l.retain(|v| *v != 3);
+        assert_eq!(values(&l), vec![1, 2, 4, 5, 6]);
+
+        let evens = l.drain_filter(|v| *v % 2 == 0);
+        assert_eq!(values(&evens), vec![2, 4, 6]);
+        assert_eq!(values(&l), vec![1, 5]);
+        assert_eq!(l.get_length(), 2);
+        assert_eq!(evens.get_length(), 3);
+        assert_eq!(l.peek_last(), Some(&5));
+    }
+
+    #[test]
+    fn dedup_search() {
+        let mut l = from_values(&[1, 1, 2, 3, 3, 3, 1]);
+        l.dedup();
+        assert_eq!(values(&l), vec![1, 2, 3, 1]);
+        assert_eq!(l.get_length(), 4);
+
+        let mut l = from_values(&[10, 11, 20, 35, 31, 12]);
+        l.dedup_by_key(|v| *v / 10);
+        assert_eq!(values(&l), vec![10, 20, 35, 12]);
+        assert_eq!(l.get_length(), 4);
+
+        assert!(l.contains(&35));
+        assert!(!l.contains(&11));
+        assert_eq!(l.position(|v| *v == 20), Some(1));
+        assert_eq!(l.position(|v| *v > 100), None);
+    }
+
+    // Ordered by the first field only, so the second field tells equal elements apart.
+    #[derive(Debug, Clone, Copy)]
+    struct Keyed(u32, usize);
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn sort() {
+        let mut rng = rand::rng();
+        for size in [0, 1, 2, 3, 7, 8, 9, 100, 257] {
+            let mut expected = Vec::new();
+            let mut l = List::new();
+            for i in 0..size {
+                let v = Keyed(rng.random_range(0..10), i);
+                expected.push(v);
+                assert!(l.push_to_beginning(v).is_ok());
+            }
+            l.reverse();
+            l.sort();
+            expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            assert_eq!(l.get_length(), size);
+            let sorted: Vec<(u32, usize)> = l.iter().map(|k| (k.0, k.1)).collect();
+            let expected: Vec<(u32, usize)> = expected.iter().map(|k| (k.0, k.1)).collect();
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn merge_sorted() {
+        let mut l = from_values(&[1, 3, 5, 7]);
+        l.merge_sorted(from_values(&[2, 3, 8, 9]));
+        assert_eq!(values(&l), vec![1, 2, 3, 3, 5, 7, 8, 9]);
+        assert_eq!(l.get_length(), 8);
+        assert_eq!(l.peek_last(), Some(&9));
+
+        let mut empty = List::new();
+        empty.merge_sorted(from_values(&[4]));
+        assert_eq!(values(&empty), vec![4]);
+    }
+}
@@ -1,47 +1,76 @@
 use bitvec::prelude::*;
 use std::mem::swap;
+use std::ops::{Bound, RangeBounds};
+use crate::collections::bit_prefix::{hamming_distance, matching_prefix_length};
+use crate::sort_algorithms::radix_sort::radix_sort_by_key;
 use rand::prelude::*;
 
 type Key = BitVec<u8, Msb0>;
 type KeySlice = BitSlice<u8, Msb0>;
 
 #[derive(Debug)]
- struct LoopyPatriciaNode {
-    left_child: Option<Box<LoopyPatriciaNode>>,
-    right_child: Option<Box<LoopyPatriciaNode>>,
-    key: Key,
-    end: bool
+pub(crate) struct LoopyPatriciaNode<V> {
+    pub(crate) left_child: Option<Box<LoopyPatriciaNode<V>>>,
+    pub(crate) right_child: Option<Box<LoopyPatriciaNode<V>>>,
+    pub(crate) key: Key,
+    // Some when a key ends at this node
+    pub(crate) value: Option<V>,
 }
 
-impl LoopyPatriciaNode {
-    fn new(k: &KeySlice, end: bool) -> Self {
+impl<V> LoopyPatriciaNode<V> {
+    fn new(k: &KeySlice, value: Option<V>) -> Self {
         let mut key = BitVec::new();
         key.extend_from_bitslice(k);
         LoopyPatriciaNode {
             left_child: None,
             right_child: None,
-            end,
+            value,
             key,
         }
     }
 }
 
 #[derive(Debug)]
-pub(crate) struct LoopyPatriciaTree {
-    root: Option<Box<LoopyPatriciaNode>>,
+pub struct PatriciaMap<V> {
+    pub(crate) root: Option<Box<LoopyPatriciaNode<V>>>,
+    length: usize,
 }
 
-impl LoopyPatriciaTree {
+impl<V> PatriciaMap<V> {
     pub fn new() -> Self {
-        LoopyPatriciaTree {
+        PatriciaMap {
             root: None,
+            length: 0,
         }
     }
 
-    pub fn insert(&mut self, original_key: &Key) {
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    // Heap and inline bytes held by the map, counting reserved capacity but not anything the
+    // values themselves own
+    pub fn memory_usage(&self) -> usize {
+        let mut total = std::mem::size_of::<Self>();
+        let mut stack: Vec<&LoopyPatriciaNode<V>> = self.root.as_deref().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            total += std::mem::size_of::<LoopyPatriciaNode<V>>() + node.key.capacity().div_ceil(8);
+            stack.extend(node.left_child.as_deref());
+            stack.extend(node.right_child.as_deref());
+        }
+        total
+    }
+
+    // Returns the previous value if the key was already present
+    pub fn insert(&mut self, original_key: &Key, value: V) -> Option<V> {
         if self.root.is_none() {
-            self.root = Some(Box::new(LoopyPatriciaNode::new(original_key.as_bitslice(), true)));
-            return;
+            self.root = Some(Box::new(LoopyPatriciaNode::new(original_key.as_bitslice(), Some(value))));
+            self.length += 1;
+            return None;
         }
 
         let mut node =  self.root.as_mut().unwrap();
@@ -54,8 +83,8 @@ impl LoopyPatriciaTree {
             let (_, old_segment) = node.key.split_at(i);
 
             if i == key_len {
-                if i!= 0 && i != node.key.len() {
-                    let mut new_child = LoopyPatriciaNode::new(old_segment, node.end);
+                if i != node.key.len() {
+                    let mut new_child = LoopyPatriciaNode::new(old_segment, node.value.take());
                     swap(&mut new_child.left_child, &mut node.left_child);
                     swap(&mut new_child.right_child, &mut node.right_child);
                     if old_segment[0] {
@@ -64,9 +93,12 @@ impl LoopyPatriciaTree {
                         node.left_child = Some(Box::new(new_child));
                     }
                     node.key.truncate(i);
-                    node.end = true;
                 }
-                return;
+                let old = node.value.replace(value);
+                if old.is_none() {
+                    self.length += 1;
+                }
+                return old;
             }
             if i == node.key.len() {
                 if key[i] {
@@ -75,7 +107,7 @@ impl LoopyPatriciaTree {
                         key = new_segment;
                         continue;
                     } else {
-                        node.right_child = Some(Box::new(LoopyPatriciaNode::new(new_segment, true)));
+                        node.right_child = Some(Box::new(LoopyPatriciaNode::new(new_segment, Some(value))));
                     }
                 } else {
                     if node.left_child.is_some() {
@@ -83,79 +115,978 @@ impl LoopyPatriciaTree {
                         key = new_segment;
                         continue;
                     } else {
-                        node.left_child = Some(Box::new(LoopyPatriciaNode::new(new_segment, true)));
+                        node.left_child = Some(Box::new(LoopyPatriciaNode::new(new_segment, Some(value))));
                     }
-                    return;
                 }
             } else {
                 let mut new_right_node;
                 let mut new_left_node;
 
                 if key[i] == false {
-                    new_left_node = LoopyPatriciaNode::new(new_segment, true);
-                    new_right_node = LoopyPatriciaNode::new(old_segment, node.end);
+                    new_left_node = LoopyPatriciaNode::new(new_segment, Some(value));
+                    new_right_node = LoopyPatriciaNode::new(old_segment, node.value.take());
                     swap(&mut new_right_node.left_child, &mut node.left_child);
                     swap(&mut new_right_node.right_child, &mut node.right_child);
                 } else {
-                    new_left_node = LoopyPatriciaNode::new(old_segment, node.end);
+                    new_left_node = LoopyPatriciaNode::new(old_segment, node.value.take());
                     swap(&mut new_left_node.left_child, &mut node.left_child);
                     swap(&mut new_left_node.right_child, &mut node.right_child);
-                    new_right_node = LoopyPatriciaNode::new(new_segment, true);
+                    new_right_node = LoopyPatriciaNode::new(new_segment, Some(value));
                 }
                 node.right_child = Some(Box::new(new_right_node));
                 node.left_child = Some(Box::new(new_left_node));
                 node.key.clear();
                 node.key.extend_from_bitslice(base_segment);
-                node.end = false;
             }
-            return;
+            self.length += 1;
+            return None;
         }
     }
 
-    fn find_number_of_matching_bits(src: &Key, dest: &KeySlice) -> usize {
-        let length = src.len().min(dest.len());
-        for i in 0..length {
-            if src[i] != dest[i] {
-                return i;
+    fn find_number_of_matching_bits(src: &KeySlice, dest: &KeySlice) -> usize {
+        matching_prefix_length(src, dest)
+    }
+
+    fn find_node(&self, original_key: &Key) -> Option<&LoopyPatriciaNode<V>> {
+        let mut node = self.root.as_ref()?;
+        let mut key = original_key.as_bitslice();
+
+        loop {
+            let i = Self::find_number_of_matching_bits(&node.key, key);
+            if i != node.key.len() {
+                return None;
+            }
+            if i == key.len() {
+                return Some(node);
             }
+
+            let (_, right) = key.split_at(i);
+            let child = if right[0] { &node.right_child } else { &node.left_child };
+            node = child.as_ref()?;
+            key = right;
         }
-        return length;
     }
 
-    pub fn search(&self, original_key: &Key) -> bool {
-        if self.root.is_none() { return false; }
+    fn find_node_mut(&mut self, original_key: &Key) -> Option<&mut LoopyPatriciaNode<V>> {
+        let mut node = self.root.as_mut()?;
+        let mut key = original_key.as_bitslice();
+
+        loop {
+            let i = Self::find_number_of_matching_bits(&node.key, key);
+            if i != node.key.len() {
+                return None;
+            }
+            if i == key.len() {
+                return Some(node);
+            }
 
-        let mut node =  self.root.as_ref().unwrap();
+            let (_, right) = key.split_at(i);
+            let child = if right[0] { &mut node.right_child } else { &mut node.left_child };
+            node = child.as_mut()?;
+            key = right;
+        }
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&V> {
+        self.find_node(key)?.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: &Key) -> Option<&mut V> {
+        self.find_node_mut(key)?.value.as_mut()
+    }
+
+    pub fn contains_key(&self, key: &Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    // The longest stored key that is a prefix of the query, along with its value
+    pub fn longest_prefix_match(&self, original_key: &KeySlice) -> Option<(Key, &V)> {
+        let mut node = self.root.as_ref()?;
+        let mut key = original_key;
+        let mut consumed = 0;
+        let mut best = None;
+
+        loop {
+            let i = Self::find_number_of_matching_bits(&node.key, key);
+            if i != node.key.len() {
+                break;
+            }
+            consumed += i;
+            if let Some(value) = &node.value {
+                best = Some((consumed, value));
+            }
+            if i == key.len() {
+                break;
+            }
+
+            let (_, right) = key.split_at(i);
+            let child = if right[0] { &node.right_child } else { &node.left_child };
+            node = match child {
+                Some(child) => child,
+                None => break,
+            };
+            key = right;
+        }
+        best.map(|(length, value)| (original_key[..length].to_bitvec(), value))
+    }
+
+    pub fn entry(&mut self, key: &Key) -> Entry<'_, V> {
+        if self.contains_key(key) {
+            Entry::Occupied(OccupiedEntry { value: self.get_mut(key).unwrap() })
+        } else {
+            Entry::Vacant(VacantEntry { map: self, key: key.clone() })
+        }
+    }
+
+    // Directions (true for right) from the root to the node holding the key
+    fn find_path(&self, original_key: &Key) -> Option<Vec<bool>> {
+        let mut node = self.root.as_ref()?;
         let mut key = original_key.as_bitslice();
+        let mut path = Vec::new();
 
         loop {
-            let i = Self::find_number_of_matching_bits(&node.key, &key);
+            let i = Self::find_number_of_matching_bits(&node.key, key);
+            if i != node.key.len() {
+                return None;
+            }
             if i == key.len() {
-                return node.end
+                return if node.value.is_some() { Some(path) } else { None };
             }
 
             let (_, right) = key.split_at(i);
+            let child = if right[0] { &node.right_child } else { &node.left_child };
+            node = child.as_ref()?;
+            path.push(right[0]);
+            key = right;
+        }
+    }
 
-            if right[0] == true {
-                if node.right_child.is_none() {
-                    return false;
+    // Removing a key only changes the shape of its own node and, if that node goes away, of its
+    // parent, so the walk stops at the parent's link.
+    pub fn remove(&mut self, original_key: &Key) -> Option<V> {
+        let path = self.find_path(original_key)?;
+        self.length -= 1;
+
+        let (last, ancestors) = match path.split_last() {
+            Some((last, ancestors)) => (*last, ancestors),
+            None => {
+                let value = self.root.as_mut().unwrap().value.take();
+                Self::compress(&mut self.root);
+                return value;
+            }
+        };
+
+        let mut link = &mut self.root;
+        for &right in ancestors {
+            let node = link.as_mut().unwrap();
+            link = if right { &mut node.right_child } else { &mut node.left_child };
+        }
+        let value = {
+            let parent = link.as_mut().unwrap();
+            let target = if last { &mut parent.right_child } else { &mut parent.left_child };
+            let value = target.as_mut().unwrap().value.take();
+            Self::compress(target);
+            value
+        };
+        Self::compress(link);
+        value
+    }
+
+    // Drop a node that no longer holds a key or leads to one, and fold a node with a single child
+    // into that child.
+    fn compress(link: &mut Option<Box<LoopyPatriciaNode<V>>>) {
+        let node = match link {
+            Some(node) if node.value.is_none() => node,
+            _ => return,
+        };
+        match (node.left_child.take(), node.right_child.take()) {
+            (None, None) => *link = None,
+            (Some(only), None) | (None, Some(only)) => {
+                let only = *only;
+                node.key.extend_from_bitslice(&only.key);
+                node.value = only.value;
+                node.left_child = only.left_child;
+                node.right_child = only.right_child;
+            }
+            (left, right) => {
+                node.left_child = left;
+                node.right_child = right;
+            }
+        }
+    }
+}
+
+// Nested keys can make the tree very deep, so unlink without recursion.
+impl<V> Drop for PatriciaMap<V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<LoopyPatriciaNode<V>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left_child.take());
+            stack.extend(node.right_child.take());
+        }
+    }
+}
+
+// Keys are ordered bit by bit with 0 before 1 and a key before its extensions, which is the
+// order of a pre-order walk taking left children first.
+impl<V> PatriciaMap<V> {
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            stack: self.root.iter().map(|root| (root.as_ref(), 0)).collect(),
+            key: Key::new(),
+        }
+    }
+
+    // Every key that starts with `prefix`, in order
+    pub fn iter_prefix(&self, prefix: &KeySlice) -> Iter<'_, V> {
+        let mut iter = Iter { stack: Vec::new(), key: prefix.to_bitvec() };
+        let mut node = match &self.root {
+            Some(root) => root,
+            None => return iter,
+        };
+        let mut consumed = 0;
+
+        loop {
+            let rest = &prefix[consumed..];
+            let i = Self::find_number_of_matching_bits(&node.key, rest);
+            if i == rest.len() {
+                iter.stack.push((node, consumed));
+                return iter;
+            }
+            if i != node.key.len() {
+                return iter;
+            }
+            consumed += i;
+            let child = if rest[i] { &node.right_child } else { &node.left_child };
+            node = match child {
+                Some(child) => child,
+                None => return iter,
+            };
+        }
+    }
+
+    // Positions an iterator on the first key not below `bound`. Right subtrees passed over on the
+    // way down hold larger keys, so they are stacked to be visited after the current branch.
+    fn seek(&self, bound: &KeySlice) -> Iter<'_, V> {
+        let mut iter = Iter { stack: Vec::new(), key: bound.to_bitvec() };
+        let mut node = match &self.root {
+            Some(root) => root,
+            None => return iter,
+        };
+        let mut consumed = 0;
+
+        loop {
+            let rest = &bound[consumed..];
+            let i = Self::find_number_of_matching_bits(&node.key, rest);
+            if i == rest.len() {
+                iter.stack.push((node, consumed));
+                return iter;
+            }
+            if i != node.key.len() {
+                if node.key[i] {
+                    iter.stack.push((node, consumed));
+                }
+                return iter;
+            }
+            consumed += i;
+            let child = if rest[i] {
+                &node.right_child
+            } else {
+                if let Some(right) = &node.right_child {
+                    iter.stack.push((right, consumed));
+                }
+                &node.left_child
+            };
+            node = match child {
+                Some(child) => child,
+                None => return iter,
+            };
+        }
+    }
+
+    pub fn range<R>(&self, range: R) -> Range<'_, V> where R: RangeBounds<Key> {
+        let iter = match range.start_bound() {
+            Bound::Included(start) | Bound::Excluded(start) => self.seek(start),
+            Bound::Unbounded => self.iter(),
+        };
+        Range {
+            iter,
+            start: range.start_bound().cloned(),
+            end: range.end_bound().cloned(),
+        }
+    }
+
+    pub fn first(&self) -> Option<(Key, &V)> {
+        self.iter().next()
+    }
+
+    pub fn last(&self) -> Option<(Key, &V)> {
+        Self::last_in(self.root.as_ref()?, Key::new())
+    }
+
+    // The largest key in a subtree is its right-most leaf
+    fn last_in(mut node: &LoopyPatriciaNode<V>, mut key: Key) -> Option<(Key, &V)> {
+        loop {
+            key.extend_from_bitslice(&node.key);
+            node = match (&node.left_child, &node.right_child) {
+                (_, Some(child)) | (Some(child), None) => child,
+                (None, None) => return node.value.as_ref().map(|value| (key, value)),
+            };
+        }
+    }
+
+    // The smallest key greater than `key`
+    pub fn successor(&self, key: &Key) -> Option<(Key, &V)> {
+        self.range((Bound::Excluded(key.clone()), Bound::Unbounded)).next()
+    }
+
+    // The largest key smaller than `key`. Each candidate met on the way down is larger than the
+    // previous one, so the last one wins.
+    pub fn predecessor(&self, original_key: &Key) -> Option<(Key, &V)> {
+        let mut node = self.root.as_ref()?;
+        let mut consumed = 0;
+        // A node whose own key is the candidate, or the root of a subtree whose last key is
+        let mut best: Option<(&LoopyPatriciaNode<V>, usize, bool)> = None;
+
+        loop {
+            let rest = &original_key[consumed..];
+            let i = Self::find_number_of_matching_bits(&node.key, rest);
+            if i == rest.len() {
+                break;
+            }
+            if i != node.key.len() {
+                if !node.key[i] {
+                    best = Some((node, consumed, true));
+                }
+                break;
+            }
+            if node.value.is_some() {
+                best = Some((node, consumed, false));
+            }
+            consumed += i;
+            if rest[i] {
+                if let Some(left) = &node.left_child {
+                    best = Some((left, consumed, true));
                 }
-                node = node.right_child.as_ref().unwrap();
-                key = right;
+            }
+            let child = if rest[i] { &node.right_child } else { &node.left_child };
+            node = match child {
+                Some(child) => child,
+                None => break,
+            };
+        }
+
+        let (node, depth, whole_subtree) = best?;
+        let key = original_key[..depth].to_bitvec();
+        if whole_subtree {
+            Self::last_in(node, key)
+        } else {
+            let mut key = key;
+            key.extend_from_bitslice(&node.key);
+            node.value.as_ref().map(|value| (key, value))
+        }
+    }
+
+    // The k keys closest to `key` by XOR distance, nearest first. XOR distance is only defined
+    // between keys of the same length, so keys of any other length are left out. Going down the
+    // child that agrees with `key` before the other one visits keys in order of distance, since
+    // the first differing bit decides which of two distances is smaller.
+    pub fn nearest_by_xor(&self, key: &KeySlice, k: usize) -> Vec<(Key, &V)> {
+        let mut nearest = Vec::new();
+        let mut stack: Vec<(&LoopyPatriciaNode<V>, usize)> = self.root.iter().map(|root| (root.as_ref(), 0)).collect();
+        let mut path = Key::new();
+
+        while let Some((node, depth)) = stack.pop() {
+            if nearest.len() == k {
+                break;
+            }
+            // Everything below a segment that runs past the end of `key` is too long
+            if depth + node.key.len() > key.len() {
+                continue;
+            }
+            path.truncate(depth);
+            path.extend_from_bitslice(&node.key);
+            let depth = path.len();
+            if depth == key.len() {
+                if let Some(value) = &node.value {
+                    nearest.push((path.clone(), value));
+                }
+                continue;
+            }
+            let (near, far) = if key[depth] {
+                (&node.right_child, &node.left_child)
+            } else {
+                (&node.left_child, &node.right_child)
+            };
+            stack.extend(far.as_deref().map(|child| (child, depth)));
+            stack.extend(near.as_deref().map(|child| (child, depth)));
+        }
+        nearest
+    }
+
+    // Every key of the same length as `key` that differs from it in at most `distance` bits, in
+    // key order. A subtree is dropped as soon as the bits on the way down to it differ too much.
+    pub fn within_hamming(&self, key: &KeySlice, distance: usize) -> Vec<(Key, &V)> {
+        let mut found = Vec::new();
+        let mut stack: Vec<(&LoopyPatriciaNode<V>, usize, usize)> =
+            self.root.iter().map(|root| (root.as_ref(), 0, 0)).collect();
+        let mut path = Key::new();
+
+        while let Some((node, depth, differences)) = stack.pop() {
+            let end = depth + node.key.len();
+            if end > key.len() {
+                continue;
+            }
+            let differences = differences + hamming_distance(&node.key, &key[depth..end]);
+            if differences > distance {
                 continue;
+            }
+            path.truncate(depth);
+            path.extend_from_bitslice(&node.key);
+            if end == key.len() {
+                if let Some(value) = &node.value {
+                    found.push((path.clone(), value));
+                }
+                continue;
+            }
+            if let Some(right) = &node.right_child {
+                stack.push((right, end, differences));
+            }
+            if let Some(left) = &node.left_child {
+                stack.push((left, end, differences));
+            }
+        }
+        found
+    }
+}
+
+pub struct Iter<'a, V> {
+    // Subtrees still to visit, with the length of the key above each one
+    stack: Vec<(&'a LoopyPatriciaNode<V>, usize)>,
+    key: Key,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Key, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, depth)) = self.stack.pop() {
+            self.key.truncate(depth);
+            self.key.extend_from_bitslice(&node.key);
+            let depth = self.key.len();
+            if let Some(right) = &node.right_child {
+                self.stack.push((right, depth));
+            }
+            if let Some(left) = &node.left_child {
+                self.stack.push((left, depth));
+            }
+            if let Some(value) = &node.value {
+                return Some((self.key.clone(), value));
+            }
+        }
+        None
+    }
+}
+
+pub struct Range<'a, V> {
+    iter: Iter<'a, V>,
+    start: Bound<Key>,
+    end: Bound<Key>,
+}
+
+impl<'a, V> Iterator for Range<'a, V> {
+    type Item = (Key, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, value) = self.iter.next()?;
+            // Seeking lands on the start key itself when it is present
+            if let Bound::Excluded(start) = &self.start {
+                if key == *start {
+                    continue;
+                }
+            }
+            let past_end = match &self.end {
+                Bound::Included(end) => key > *end,
+                Bound::Excluded(end) => key >= *end,
+                Bound::Unbounded => false,
+            };
+            if past_end {
+                self.iter.stack.clear();
+                return None;
+            }
+            return Some((key, value));
+        }
+    }
+}
+
+// Bulk loading
+impl<V> PatriciaMap<V> {
+    // Builds the tree in one pass from keys in strictly increasing order, failing on keys that
+    // are out of order or repeated. Only the right-most path is still open to new keys; each
+    // key closes the part of that path below its common prefix with the previous key.
+    pub fn from_sorted_iter<I>(items: I) -> Result<Self, ()> where I: IntoIterator<Item = (Key, V)> {
+        let mut items = items.into_iter();
+        let (mut previous, value) = match items.next() {
+            Some(first) => first,
+            None => return Ok(Self::new()),
+        };
+        // Open nodes from the root down, with the number of key bits above each one
+        let mut path = vec![(Box::new(LoopyPatriciaNode::new(&previous, Some(value))), 0)];
+        let mut length = 1;
+
+        for (key, value) in items {
+            let common = Self::find_number_of_matching_bits(&previous, &key);
+            let increasing = if common == previous.len() {
+                key.len() > common
+            } else {
+                common < key.len() && key[common]
+            };
+            if !increasing {
+                return Err(());
+            }
+
+            while path.len() > 1 && path.last().unwrap().1 >= common {
+                Self::close_last(&mut path);
+            }
+            let (node, above) = path.last_mut().unwrap();
+            let at = common - *above;
+            if at < node.key.len() {
+                // The previous key branches off with a 0 here, so the old tail goes left
+                let mut tail = LoopyPatriciaNode::new(&node.key[at..], node.value.take());
+                tail.left_child = node.left_child.take();
+                tail.right_child = node.right_child.take();
+                node.key.truncate(at);
+                node.left_child = Some(Box::new(tail));
+            }
+
+            path.push((Box::new(LoopyPatriciaNode::new(&key[common..], Some(value))), common));
+            length += 1;
+            previous = key;
+        }
+
+        while path.len() > 1 {
+            Self::close_last(&mut path);
+        }
+        Ok(PatriciaMap {
+            root: path.pop().map(|(root, _)| root),
+            length,
+        })
+    }
+
+    fn close_last(path: &mut Vec<(Box<LoopyPatriciaNode<V>>, usize)>) {
+        let (node, _) = path.pop().unwrap();
+        let (parent, _) = path.last_mut().unwrap();
+        if node.key[0] {
+            parent.right_child = Some(node);
+        } else {
+            parent.left_child = Some(node);
+        }
+    }
+}
+
+// Sorts with the radix sort first; for repeated keys the last value wins
+impl<V> FromIterator<(Key, V)> for PatriciaMap<V> {
+    fn from_iter<I>(items: I) -> Self where I: IntoIterator<Item = (Key, V)> {
+        let mut items: Vec<(Key, usize, V)> = items
+            .into_iter()
+            .enumerate()
+            .map(|(index, (key, value))| (key, index, value))
+            .collect();
+        radix_sort_by_key(&mut items, |item| &item.0);
+
+        let mut unique: Vec<(Key, V)> = Vec::with_capacity(items.len());
+        let mut latest = 0;
+        for (key, index, value) in items {
+            match unique.last_mut() {
+                Some(last) if last.0 == key => {
+                    if index > latest {
+                        last.1 = value;
+                        latest = index;
+                    }
+                }
+                _ => {
+                    unique.push((key, value));
+                    latest = index;
+                }
+            }
+        }
+        Self::from_sorted_iter(unique).unwrap()
+    }
+}
+
+// Which keys a set operation keeps: those only in the left tree, only in the right one, or in
+// both. Values come from the left tree where both have the key.
+#[derive(Clone, Copy)]
+struct SetOperation {
+    left_only: bool,
+    right_only: bool,
+    both: bool,
+}
+
+// A node whose key is taken to start `usize` bits in, so that a walk can stand in the middle of
+// a compressed segment.
+type Cursor<'a, V> = Option<(&'a LoopyPatriciaNode<V>, usize)>;
+
+type Pair<'a, V> = (Cursor<'a, V>, Cursor<'a, V>);
+
+fn child<V>(node: &LoopyPatriciaNode<V>, bit: bool) -> Cursor<'_, V> {
+    let child = if bit { &node.right_child } else { &node.left_child };
+    child.as_deref().map(|child| (child, 0))
+}
+
+// Work left for a set operation: a pair of subtrees to combine, or a node waiting for its two
+// combined children
+enum Step<'a, V> {
+    Combine(Pair<'a, V>),
+    Finish(LoopyPatriciaNode<V>),
+}
+
+// Set operations walk both trees together. Where the two trees diverge the subtrees have no
+// keys in common, so whole subtrees are copied or skipped without looking at their keys.
+impl<V> PatriciaMap<V> where V: Clone {
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, SetOperation { left_only: true, right_only: true, both: true })
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, SetOperation { left_only: false, right_only: false, both: true })
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine(other, SetOperation { left_only: true, right_only: false, both: false })
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.combine(other, SetOperation { left_only: true, right_only: true, both: false })
+    }
+
+    fn combine(&self, other: &Self, operation: SetOperation) -> Self {
+        let mut length = 0;
+        let mut steps = vec![Step::Combine((self.root.as_deref().map(|root| (root, 0)), other.root.as_deref().map(|root| (root, 0))))];
+        // Finished subtrees, the left one of a pair below the right one
+        let mut done: Vec<Option<Box<LoopyPatriciaNode<V>>>> = Vec::new();
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Combine((a, b)) => match Self::combine_nodes(a, b, &operation) {
+                    Some((node, [left, right])) => {
+                        steps.push(Step::Finish(node));
+                        steps.push(Step::Combine(right));
+                        steps.push(Step::Combine(left));
+                    }
+                    None => done.push(None),
+                },
+                Step::Finish(mut node) => {
+                    node.right_child = done.pop().unwrap();
+                    node.left_child = done.pop().unwrap();
+                    if node.value.is_some() {
+                        length += 1;
+                    }
+                    let mut link = Some(Box::new(node));
+                    Self::compress(&mut link);
+                    done.push(link);
+                }
+            }
+        }
+        PatriciaMap { root: done.pop().unwrap(), length }
+    }
+
+    // The node where two subtrees meet, without its children, and the pairs of subtrees that
+    // combine into its left and right child. A subtree facing nothing is copied whole or left out.
+    fn combine_nodes<'a>(a: Cursor<'a, V>, b: Cursor<'a, V>, operation: &SetOperation) -> Option<(LoopyPatriciaNode<V>, [Pair<'a, V>; 2])> {
+        let (a, b) = match (a, b) {
+            (None, None) => return None,
+            (Some(a), None) if operation.left_only => {
+                let node = LoopyPatriciaNode::new(&a.0.key[a.1..], a.0.value.clone());
+                return Some((node, [(child(a.0, false), None), (child(a.0, true), None)]));
+            }
+            (None, Some(b)) if operation.right_only => {
+                let node = LoopyPatriciaNode::new(&b.0.key[b.1..], b.0.value.clone());
+                return Some((node, [(None, child(b.0, false)), (None, child(b.0, true))]));
+            }
+            (Some(a), Some(b)) => (a, b),
+            _ => return None,
+        };
+        let (a_key, b_key) = (&a.0.key[a.1..], &b.0.key[b.1..]);
+        let i = Self::find_number_of_matching_bits(a_key, b_key);
+        let mut node = LoopyPatriciaNode::new(&a_key[..i], None);
+
+        let children = if i == a_key.len() && i == b_key.len() {
+            node.value = match (&a.0.value, &b.0.value) {
+                (Some(value), Some(_)) if operation.both => Some(value.clone()),
+                (Some(value), None) if operation.left_only => Some(value.clone()),
+                (None, Some(value)) if operation.right_only => Some(value.clone()),
+                _ => None,
+            };
+            [(child(a.0, false), child(b.0, false)), (child(a.0, true), child(b.0, true))]
+        } else if i == a_key.len() {
+            // The right key runs on below this node, so nothing ends here on the right side
+            if operation.left_only {
+                node.value = a.0.value.clone();
+            }
+            let bit = b_key[i];
+            let below = (child(a.0, bit), Some((b.0, b.1 + i)));
+            let beside = (child(a.0, !bit), None);
+            if bit { [beside, below] } else { [below, beside] }
+        } else if i == b_key.len() {
+            if operation.right_only {
+                node.value = b.0.value.clone();
+            }
+            let bit = a_key[i];
+            let below = (Some((a.0, a.1 + i)), child(b.0, bit));
+            let beside = (None, child(b.0, !bit));
+            if bit { [beside, below] } else { [below, beside] }
+        } else {
+            let a_rest = (Some((a.0, a.1 + i)), None);
+            let b_rest = (None, Some((b.0, b.1 + i)));
+            if a_key[i] { [b_rest, a_rest] } else { [a_rest, b_rest] }
+        };
+        Some((node, children))
+    }
+}
+
+// Chains of nested keys make trees as deep as they have keys, so the walks keep their own stacks.
+impl<V> PatriciaMap<V> {
+    // Whether every key of this map is also a key of `other`
+    pub fn is_subset<W>(&self, other: &PatriciaMap<W>) -> bool {
+        let mut stack = vec![(self.root.as_deref().map(|root| (root, 0)), other.root.as_deref().map(|root| (root, 0)))];
+        while let Some(pair) = stack.pop() {
+            // Every subtree holds at least one key
+            let (a, b) = match pair {
+                (None, _) => continue,
+                (Some(_), None) => return false,
+                (Some(a), Some(b)) => (a, b),
+            };
+            let (a_key, b_key) = (&a.0.key[a.1..], &b.0.key[b.1..]);
+            let i = matching_prefix_length(a_key, b_key);
+            if i == a_key.len() && i == b_key.len() {
+                if a.0.value.is_some() && b.0.value.is_none() {
+                    return false;
+                }
+                stack.push((child(a.0, false), child(b.0, false)));
+                stack.push((child(a.0, true), child(b.0, true)));
+            } else if i == a_key.len() {
+                let bit = b_key[i];
+                if a.0.value.is_some() || child(a.0, !bit).is_some() {
+                    return false;
+                }
+                stack.push((child(a.0, bit), Some((b.0, b.1 + i))));
+            } else if i == b_key.len() {
+                stack.push((Some((a.0, a.1 + i)), child(b.0, a_key[i])));
             } else {
-                if node.left_child.is_none() {
+                return false;
+            }
+        }
+        true
+    }
+
+    // Whether the two maps have no key in common
+    pub fn is_disjoint<W>(&self, other: &PatriciaMap<W>) -> bool {
+        let mut stack = vec![(self.root.as_deref().map(|root| (root, 0)), other.root.as_deref().map(|root| (root, 0)))];
+        while let Some(pair) = stack.pop() {
+            let (a, b) = match pair {
+                (Some(a), Some(b)) => (a, b),
+                _ => continue,
+            };
+            let (a_key, b_key) = (&a.0.key[a.1..], &b.0.key[b.1..]);
+            let i = matching_prefix_length(a_key, b_key);
+            if i == a_key.len() && i == b_key.len() {
+                if a.0.value.is_some() && b.0.value.is_some() {
                     return false;
                 }
-                node = node.left_child.as_ref().unwrap();
-                key = right;
+                stack.push((child(a.0, false), child(b.0, false)));
+                stack.push((child(a.0, true), child(b.0, true)));
+            } else if i == a_key.len() {
+                stack.push((child(a.0, b_key[i]), Some((b.0, b.1 + i))));
+            } else if i == b_key.len() {
+                stack.push((Some((a.0, a.1 + i)), child(b.0, a_key[i])));
             }
         }
+        true
     }
+}
+
+pub enum Entry<'a, V> {
+    Occupied(OccupiedEntry<'a, V>),
+    Vacant(VacantEntry<'a, V>),
+}
 
-    // fn delete(&mut self, _key: &BitVec) {
-    //     unimplemented!(); // Implement delete method if needed
-    // }
+pub struct OccupiedEntry<'a, V> {
+    value: &'a mut V,
+}
+
+pub struct VacantEntry<'a, V> {
+    map: &'a mut PatriciaMap<V>,
+    key: Key,
+}
+
+impl<'a, V> Entry<'a, V> {
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V where F: FnOnce() -> V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V where V: Default {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F>(mut self, f: F) -> Self where F: FnOnce(&mut V) {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.value);
+        }
+        self
+    }
+}
+
+impl<'a, V> OccupiedEntry<'a, V> {
+    pub fn get(&self) -> &V {
+        self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.value
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.value
+    }
+
+    // Replaces the value, returning the old one
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.value, value)
+    }
+}
+
+impl<'a, V> VacantEntry<'a, V> {
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.insert(&self.key, value);
+        self.map.get_mut(&self.key).unwrap()
+    }
+}
+
+// The key set is a map without values
+#[derive(Debug)]
+pub struct LoopyPatriciaTree {
+    map: PatriciaMap<()>,
+}
+
+impl LoopyPatriciaTree {
+    pub fn new() -> Self {
+        LoopyPatriciaTree {
+            map: PatriciaMap::new(),
+        }
+    }
+
+    // Fails on keys that are out of order or repeated
+    pub fn from_sorted_iter<I>(keys: I) -> Result<Self, ()> where I: IntoIterator<Item = Key> {
+        Ok(LoopyPatriciaTree {
+            map: PatriciaMap::from_sorted_iter(keys.into_iter().map(|key| (key, ())))?,
+        })
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.map.get_length()
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.map.memory_usage()
+    }
+
+    // Returns false if the key was already in the tree
+    pub fn insert(&mut self, key: &Key) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    pub fn search(&self, key: &Key) -> bool {
+        self.map.contains_key(key)
+    }
+
+    // Remove a key from the tree, returns false if it was not there
+    pub fn remove(&mut self, key: &Key) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    pub fn longest_prefix_match(&self, key: &KeySlice) -> Option<Key> {
+        self.map.longest_prefix_match(key).map(|(prefix, _)| prefix)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Key> + '_ {
+        self.map.iter().map(|(key, _)| key)
+    }
+
+    pub fn iter_prefix(&self, prefix: &KeySlice) -> impl Iterator<Item = Key> + '_ {
+        self.map.iter_prefix(prefix).map(|(key, _)| key)
+    }
+
+    pub fn range<R>(&self, range: R) -> impl Iterator<Item = Key> + '_ where R: RangeBounds<Key> {
+        self.map.range(range).map(|(key, _)| key)
+    }
+
+    pub fn first(&self) -> Option<Key> {
+        self.map.first().map(|(key, _)| key)
+    }
+
+    pub fn last(&self) -> Option<Key> {
+        self.map.last().map(|(key, _)| key)
+    }
+
+    pub fn successor(&self, key: &Key) -> Option<Key> {
+        self.map.successor(key).map(|(key, _)| key)
+    }
+
+    pub fn predecessor(&self, key: &Key) -> Option<Key> {
+        self.map.predecessor(key).map(|(key, _)| key)
+    }
+
+    pub fn nearest_by_xor(&self, key: &KeySlice, k: usize) -> Vec<Key> {
+        self.map.nearest_by_xor(key, k).into_iter().map(|(key, _)| key).collect()
+    }
+
+    pub fn within_hamming(&self, key: &KeySlice, distance: usize) -> Vec<Key> {
+        self.map.within_hamming(key, distance).into_iter().map(|(key, _)| key).collect()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        LoopyPatriciaTree { map: self.map.union(&other.map) }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        LoopyPatriciaTree { map: self.map.intersection(&other.map) }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        LoopyPatriciaTree { map: self.map.difference(&other.map) }
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        LoopyPatriciaTree { map: self.map.symmetric_difference(&other.map) }
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.map.is_subset(&other.map)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.map.is_disjoint(&other.map)
+    }
+}
+
+impl FromIterator<Key> for LoopyPatriciaTree {
+    fn from_iter<I>(keys: I) -> Self where I: IntoIterator<Item = Key> {
+        LoopyPatriciaTree {
+            map: keys.into_iter().map(|key| (key, ())).collect(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -193,8 +1124,8 @@ mod tests {
     }
 
 
-    fn dfs(p: &LoopyPatriciaNode, s: &str) {
-        let text = format!("{}-{}{}",s, p.key, if p.end {"+(end)"} else { "" });
+    fn dfs<V>(p: &LoopyPatriciaNode<V>, s: &str) {
+        let text = format!("{}-{}{}",s, p.key, if p.value.is_some() {"+(end)"} else { "" });
         if p.left_child.is_some() {
             dfs(p.left_child.as_ref().unwrap(), text.as_str());
         }
@@ -206,5 +1137,504 @@ mod tests {
             println!("{}", text);
         }
     }
-}
 
+    fn short_key(rng: &mut impl Rng) -> Key {
+        let length = rng.random_range(0..12);
+        (0..length).map(|_| rng.random_bool(0.5)).collect()
+    }
+
+    #[test]
+    fn prefixes_and_remove() {
+        let mut p = LoopyPatriciaTree::new();
+        let keys: Vec<Key> = [&[][..], &[true], &[true, false, true], &[true, false], &[false, false]]
+            .iter()
+            .map(|bits| bits.iter().collect())
+            .collect();
+        for key in &keys[1..] {
+            p.insert(key);
+        }
+        assert!(!p.search(&keys[0]));
+        assert!(!p.search(&bits![u8, Msb0; 1, 0, 1, 1].to_bitvec()));
+        assert!(!p.search(&bits![u8, Msb0; 0].to_bitvec()));
+        p.insert(&keys[0]);
+        for key in &keys {
+            assert!(p.search(key));
+        }
+
+        assert!(p.remove(&keys[3]));
+        assert!(!p.remove(&keys[3]));
+        assert!(!p.search(&keys[3]));
+        assert!(p.search(&keys[2]));
+        // 1 and 101 are left under the right branch, 10 has been folded into its child.
+        let root = p.map.root.as_ref().unwrap();
+        assert!(root.key.is_empty());
+        let right = root.right_child.as_ref().unwrap();
+        assert_eq!(right.key, bits![u8, Msb0; 1]);
+        assert_eq!(right.left_child.as_ref().unwrap().key, bits![u8, Msb0; 0, 1]);
+
+        for key in &keys {
+            p.remove(key);
+        }
+        assert!(p.map.root.is_none());
+    }
+
+    // Every node below the root either holds a key or branches both ways.
+    fn assert_compressed<V>(node: &LoopyPatriciaNode<V>, is_root: bool) {
+        let children = [&node.left_child, &node.right_child];
+        if !is_root {
+            assert!(node.value.is_some() || children.iter().all(|c| c.is_some()));
+        }
+        for child in children.into_iter().flatten() {
+            assert_compressed(child, false);
+        }
+    }
+
+    #[test]
+    fn random_insert_remove_against_hash_set() {
+        let mut rng = rand::rng();
+        let mut p = LoopyPatriciaTree::new();
+        let mut oracle = std::collections::HashSet::new();
+
+        for _ in 0..5000 {
+            let key = short_key(&mut rng);
+            if rng.random_bool(0.5) {
+                p.insert(&key);
+                oracle.insert(key);
+            } else {
+                assert_eq!(p.remove(&key), oracle.remove(&key));
+            }
+            let probe = short_key(&mut rng);
+            assert_eq!(p.search(&probe), oracle.contains(&probe));
+            if let Some(root) = &p.map.root {
+                assert_compressed(root, true);
+            }
+        }
+        for key in &oracle {
+            assert!(p.search(key));
+            assert!(p.remove(key));
+        }
+        assert!(p.map.root.is_none());
+    }
+
+
+    #[test]
+    fn map_insert_get_remove() {
+        let mut map = PatriciaMap::new();
+        let a = bits![u8, Msb0; 1, 0, 1].to_bitvec();
+        let b = bits![u8, Msb0; 1, 0].to_bitvec();
+        let c = bits![u8, Msb0; 0].to_bitvec();
+
+        assert_eq!(map.insert(&a, "a"), None);
+        assert_eq!(map.insert(&b, "b"), None);
+        assert_eq!(map.insert(&a, "A"), Some("a"));
+        assert_eq!(map.get_length(), 2);
+        assert_eq!(map.get(&a), Some(&"A"));
+        assert_eq!(map.get(&c), None);
+
+        if let Some(v) = map.get_mut(&b) {
+            *v = "B";
+        }
+        assert_eq!(map.get(&b), Some(&"B"));
+        assert_eq!(map.remove(&b), Some("B"));
+        assert_eq!(map.remove(&b), None);
+        assert_eq!(map.get(&a), Some(&"A"));
+        assert_eq!(map.get_length(), 1);
+    }
+
+    #[test]
+    fn entry_api() {
+        let mut counts: PatriciaMap<u32> = PatriciaMap::new();
+        let words = ["ab", "a", "ab", "b", "ab", "a"];
+        for word in words {
+            let key = word.as_bytes().view_bits::<Msb0>().to_bitvec();
+            *counts.entry(&key).or_default() += 1;
+        }
+        let key = |word: &str| word.as_bytes().view_bits::<Msb0>().to_bitvec();
+        assert_eq!(counts.get(&key("ab")), Some(&3));
+        assert_eq!(counts.get(&key("a")), Some(&2));
+        assert_eq!(counts.get(&key("b")), Some(&1));
+
+        counts.entry(&key("b")).and_modify(|v| *v *= 10).or_insert(0);
+        counts.entry(&key("c")).and_modify(|v| *v *= 10).or_insert(7);
+        assert_eq!(counts.get(&key("b")), Some(&10));
+        assert_eq!(counts.get(&key("c")), Some(&7));
+
+        match counts.entry(&key("a")) {
+            Entry::Occupied(mut entry) => assert_eq!(entry.insert(5), 2),
+            Entry::Vacant(_) => panic!("a is present"),
+        }
+        match counts.entry(&key("d")) {
+            Entry::Occupied(_) => panic!("d is absent"),
+            Entry::Vacant(entry) => {
+                assert_eq!(entry.key(), &key("d"));
+                *entry.insert(1) += 1;
+            }
+        }
+        assert_eq!(counts.get(&key("a")), Some(&5));
+        assert_eq!(counts.get(&key("d")), Some(&2));
+        assert_eq!(counts.get_length(), 5);
+    }
+
+    #[test]
+    fn random_map_against_hash_map() {
+        let mut rng = rand::rng();
+        let mut map = PatriciaMap::new();
+        let mut oracle = std::collections::HashMap::new();
+
+        for step in 0..5000 {
+            let key = short_key(&mut rng);
+            match rng.random_range(0..3) {
+                0 => assert_eq!(map.insert(&key, step), oracle.insert(key, step)),
+                1 => assert_eq!(map.remove(&key), oracle.remove(&key)),
+                _ => {
+                    *map.entry(&key).or_insert(0) += 1;
+                    *oracle.entry(key).or_insert(0) += 1;
+                }
+            }
+            let probe = short_key(&mut rng);
+            assert_eq!(map.get(&probe), oracle.get(&probe));
+            assert_eq!(map.get_length(), oracle.len());
+        }
+        for (key, value) in &oracle {
+            assert_eq!(map.get(key), Some(value));
+        }
+    }
+
+
+    #[test]
+    fn longest_prefix_match() {
+        let mut map = PatriciaMap::new();
+        for (bits, value) in [("1", 1), ("101", 2), ("10110", 3), ("0", 4)] {
+            let key: Key = bits.chars().map(|c| c == '1').collect();
+            map.insert(&key, value);
+        }
+        let query = |bits: &str| {
+            let key: Key = bits.chars().map(|c| c == '1').collect();
+            map.longest_prefix_match(&key).map(|(prefix, value)| (prefix.len(), *value))
+        };
+        assert_eq!(query("1011011"), Some((5, 3)));
+        assert_eq!(query("10111"), Some((3, 2)));
+        assert_eq!(query("10"), Some((1, 1)));
+        assert_eq!(query("110"), Some((1, 1)));
+        assert_eq!(query("0111"), Some((1, 4)));
+        assert_eq!(query(""), None);
+
+        let mut set = LoopyPatriciaTree::new();
+        assert_eq!(set.longest_prefix_match(bits![u8, Msb0; 1]), None);
+        set.insert(&Key::new());
+        assert_eq!(set.longest_prefix_match(bits![u8, Msb0; 1]), Some(Key::new()));
+    }
+
+    #[test]
+    fn random_longest_prefix_match_against_brute_force() {
+        let mut rng = rand::rng();
+        let mut map = PatriciaMap::new();
+        let mut keys = Vec::new();
+        for step in 0..300 {
+            let key = short_key(&mut rng);
+            map.insert(&key, step);
+            keys.push(key);
+        }
+
+        for _ in 0..2000 {
+            let query = short_key(&mut rng);
+            let expected = keys
+                .iter()
+                .filter(|key| query.starts_with(key))
+                .max_by_key(|key| key.len())
+                .map(|key| (key.clone(), map.get(key).unwrap()));
+            assert_eq!(map.longest_prefix_match(&query), expected);
+        }
+    }
+
+
+    #[test]
+    fn ordered_traversal() {
+        let mut set = LoopyPatriciaTree::new();
+        let key = |bits: &str| -> Key { bits.chars().map(|c| c == '1').collect() };
+        let text = |key: Key| -> String { key.iter().map(|b| if *b { '1' } else { '0' }).collect() };
+        for bits in ["101", "1", "0011", "", "11", "1010", "00"] {
+            set.insert(&key(bits));
+        }
+
+        let all: Vec<String> = set.iter().map(text).collect();
+        assert_eq!(all, vec!["", "00", "0011", "1", "101", "1010", "11"]);
+        let under: Vec<String> = set.iter_prefix(&key("10")).map(text).collect();
+        assert_eq!(under, vec!["101", "1010"]);
+        assert_eq!(set.iter_prefix(&key("01")).count(), 0);
+
+        let between: Vec<String> = set.range(key("001")..key("101")).map(text).collect();
+        assert_eq!(between, vec!["0011", "1"]);
+        let between: Vec<String> = set.range((Bound::Excluded(key("101")), Bound::Included(key("11")))).map(text).collect();
+        assert_eq!(between, vec!["1010", "11"]);
+
+        assert_eq!(set.first().map(text), Some("".to_string()));
+        assert_eq!(set.last().map(text), Some("11".to_string()));
+        assert_eq!(set.successor(&key("1")).map(text), Some("101".to_string()));
+        assert_eq!(set.successor(&key("0100")).map(text), Some("1".to_string()));
+        assert_eq!(set.successor(&key("11")), None);
+        assert_eq!(set.predecessor(&key("1011")).map(text), Some("1010".to_string()));
+        assert_eq!(set.predecessor(&key("1")).map(text), Some("0011".to_string()));
+        assert_eq!(set.predecessor(&key("")), None);
+    }
+
+    #[test]
+    fn random_ordered_queries_against_btree_set() {
+        let mut rng = rand::rng();
+        let mut set = LoopyPatriciaTree::new();
+        let mut oracle = std::collections::BTreeSet::new();
+        for _ in 0..400 {
+            let key = short_key(&mut rng);
+            if rng.random_bool(0.8) {
+                set.insert(&key);
+                oracle.insert(key);
+            } else {
+                set.remove(&key);
+                oracle.remove(&key);
+            }
+        }
+
+        assert!(set.iter().eq(oracle.iter().cloned()));
+        assert_eq!(set.first().as_ref(), oracle.first());
+        assert_eq!(set.last().as_ref(), oracle.last());
+
+        for _ in 0..500 {
+            let a = short_key(&mut rng);
+            let b = short_key(&mut rng);
+            assert!(set.iter_prefix(&a).eq(oracle.iter().filter(|k| k.starts_with(&a)).cloned()));
+            assert_eq!(set.successor(&a).as_ref(), oracle.range::<Key, _>((Bound::Excluded(&a), Bound::Unbounded)).next());
+            assert_eq!(set.predecessor(&a).as_ref(), oracle.range::<Key, _>(..&a).next_back());
+
+            let (low, high) = if a <= b { (a, b) } else { (b, a) };
+            assert!(set.range(low.clone()..high.clone()).eq(oracle.range(low.clone()..high.clone()).cloned()));
+            assert!(set.range(low.clone()..=high.clone()).eq(oracle.range(low.clone()..=high.clone()).cloned()));
+            let bounds = (Bound::Excluded(low.clone()), Bound::Unbounded);
+            assert!(set.range(bounds.clone()).eq(oracle.range(bounds).cloned()));
+            assert!(set.range(..high.clone()).eq(oracle.range(..high).cloned()));
+        }
+    }
+
+
+    #[test]
+    fn from_sorted_iter_matches_inserts() {
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            let mut keys: Vec<Key> = (0..rng.random_range(0..200)).map(|_| short_key(&mut rng)).collect();
+            keys.sort();
+            keys.dedup();
+
+            let bulk = LoopyPatriciaTree::from_sorted_iter(keys.clone()).unwrap();
+            let mut inserted = LoopyPatriciaTree::new();
+            for key in &keys {
+                inserted.insert(key);
+            }
+            assert!(bulk.iter().eq(keys.iter().cloned()));
+            assert_eq!(bulk.get_length(), keys.len());
+            if let Some(root) = &bulk.map.root {
+                assert_compressed(root, false);
+            }
+            for _ in 0..20 {
+                let probe = short_key(&mut rng);
+                assert_eq!(bulk.search(&probe), inserted.search(&probe));
+            }
+        }
+    }
+
+    #[test]
+    fn from_sorted_iter_rejects_bad_order() {
+        let key = |bits: &str| -> Key { bits.chars().map(|c| c == '1').collect() };
+        let unsorted = ["0", "01", "1", "10", "011"].map(key);
+        assert!(LoopyPatriciaTree::from_sorted_iter(unsorted).is_err());
+        let repeated = ["0", "01", "01"].map(key);
+        assert!(LoopyPatriciaTree::from_sorted_iter(repeated).is_err());
+        let prefix_after = ["01", "0"].map(key);
+        assert!(LoopyPatriciaTree::from_sorted_iter(prefix_after).is_err());
+        assert_eq!(LoopyPatriciaTree::from_sorted_iter(Vec::new()).unwrap().get_length(), 0);
+    }
+
+    #[test]
+    fn from_iter_sorts_and_keeps_last_value() {
+        let mut rng = rand::rng();
+        let mut oracle = std::collections::BTreeMap::new();
+        let mut pairs = Vec::new();
+        for step in 0..2000 {
+            let key = short_key(&mut rng);
+            oracle.insert(key.clone(), step);
+            pairs.push((key, step));
+        }
+
+        let map: PatriciaMap<i32> = pairs.into_iter().collect();
+        assert_eq!(map.get_length(), oracle.len());
+        assert!(map.iter().eq(oracle.iter().map(|(k, v)| (k.clone(), v))));
+
+        let set: LoopyPatriciaTree = oracle.keys().rev().cloned().collect();
+        assert!(set.iter().eq(oracle.keys().cloned()));
+    }
+
+    #[test]
+    #[ignore]
+    fn benchmark_bulk_load() {
+        const SIZE: usize = 512;
+        const COUNT: usize = 100_000;
+        let mut rng = rand::rng();
+        let mut keys: Vec<Key> = (0..COUNT)
+            .map(|_| {
+                let mut bytes = [0u8; SIZE];
+                rng.fill_bytes(&mut bytes);
+                bytes.view_bits::<Msb0>().to_bitvec()
+            })
+            .collect();
+
+        let start = std::time::Instant::now();
+        let unsorted: LoopyPatriciaTree = keys.iter().cloned().collect();
+        println!("{} keys sorted and bulk loaded in {:?}", COUNT, start.elapsed());
+
+        keys.sort();
+        let start = std::time::Instant::now();
+        let mut inserted = LoopyPatriciaTree::new();
+        for key in &keys {
+            inserted.insert(key);
+        }
+        println!("{} sorted keys inserted one by one in {:?}", COUNT, start.elapsed());
+
+        let start = std::time::Instant::now();
+        let bulk = LoopyPatriciaTree::from_sorted_iter(keys).unwrap();
+        println!("{} sorted keys bulk loaded in {:?}", COUNT, start.elapsed());
+        assert_eq!(bulk.get_length(), inserted.get_length());
+        assert_eq!(unsorted.get_length(), inserted.get_length());
+    }
+
+
+    #[test]
+    fn random_set_operations_against_btree_set() {
+        let mut rng = rand::rng();
+        for _ in 0..200 {
+            let sizes = [rng.random_range(0..60), rng.random_range(0..60)];
+            let oracles: Vec<std::collections::BTreeSet<Key>> =
+                sizes.iter().map(|&n| (0..n).map(|_| short_key(&mut rng)).collect()).collect();
+            let a: LoopyPatriciaTree = oracles[0].iter().cloned().collect();
+            let b: LoopyPatriciaTree = oracles[1].iter().cloned().collect();
+
+            let results = [
+                (a.union(&b), oracles[0].union(&oracles[1]).cloned().collect::<Vec<_>>()),
+                (a.intersection(&b), oracles[0].intersection(&oracles[1]).cloned().collect()),
+                (a.difference(&b), oracles[0].difference(&oracles[1]).cloned().collect()),
+                (a.symmetric_difference(&b), oracles[0].symmetric_difference(&oracles[1]).cloned().collect()),
+            ];
+            for (set, expected) in &results {
+                assert!(set.iter().eq(expected.iter().cloned()));
+                assert_eq!(set.get_length(), expected.len());
+                if let Some(root) = &set.map.root {
+                    assert_compressed(root, true);
+                }
+            }
+
+            assert_eq!(a.is_subset(&b), oracles[0].is_subset(&oracles[1]));
+            assert_eq!(a.is_disjoint(&b), oracles[0].is_disjoint(&oracles[1]));
+            let part: LoopyPatriciaTree = oracles[0].iter().filter(|_| rng.random_bool(0.5)).cloned().collect();
+            assert!(part.is_subset(&a));
+            assert!(part.is_disjoint(&a.difference(&part)));
+            assert_eq!(a.is_subset(&part), part.get_length() == a.get_length());
+        }
+    }
+
+    #[test]
+    fn set_operations_keep_left_values() {
+        let key = |text: &str| text.chars().map(|c| c == '1').collect::<Key>();
+        let a: PatriciaMap<i32> = [(key("0"), 1), (key("01"), 2), (key("11"), 3)].into_iter().collect();
+        let b: PatriciaMap<i32> = [(key("01"), 20), (key("1"), 30)].into_iter().collect();
+
+        let union = a.union(&b);
+        assert_eq!(union.get_length(), 4);
+        assert_eq!(union.get(&key("01")), Some(&2));
+        assert_eq!(union.get(&key("1")), Some(&30));
+        let intersection = b.intersection(&a);
+        assert_eq!(intersection.iter().map(|(_, v)| *v).collect::<Vec<_>>(), vec![20]);
+        assert!(a.difference(&a).is_empty());
+        assert!(!a.is_subset(&b));
+        assert!(a.intersection(&b).is_subset(&b));
+        assert!(a.difference(&b).is_disjoint(&b));
+    }
+
+
+    #[test]
+    fn random_nearest_and_hamming_against_brute_force() {
+        let mut rng = rand::rng();
+        let distance = |a: &KeySlice, b: &KeySlice| -> (usize, Vec<bool>) {
+            (a.iter().zip(b.iter()).filter(|(x, y)| x != y).count(), a.iter().zip(b.iter()).map(|(x, y)| *x != *y).collect())
+        };
+        for _ in 0..50 {
+            // Mostly 10 bit keys, with some shorter and longer ones that must never be returned
+            let keys: std::collections::BTreeSet<Key> = (0..rng.random_range(0..300))
+                .map(|_| {
+                    let length = if rng.random_bool(0.8) { 10 } else { rng.random_range(0..14) };
+                    (0..length).map(|_| rng.random_bool(0.5)).collect()
+                })
+                .collect();
+            let set: LoopyPatriciaTree = keys.iter().cloned().collect();
+
+            for _ in 0..20 {
+                let query: Key = (0..10).map(|_| rng.random_bool(0.5)).collect();
+                let mut by_xor: Vec<&Key> = keys.iter().filter(|key| key.len() == query.len()).collect();
+                by_xor.sort_by_key(|key| distance(key, &query).1);
+                let k = rng.random_range(0..10);
+                let nearest = set.nearest_by_xor(&query, k);
+                assert!(nearest.iter().eq(by_xor.iter().take(k).cloned()));
+
+                let d = rng.random_range(0..5);
+                let expected: Vec<&Key> =
+                    keys.iter().filter(|key| key.len() == query.len() && distance(key, &query).0 <= d).collect();
+                assert!(set.within_hamming(&query, d).iter().eq(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn nearest_by_xor_on_map() {
+        let key = |text: &str| text.chars().map(|c| c == '1').collect::<Key>();
+        let map: PatriciaMap<&str> =
+            [(key("0000"), "a"), (key("0011"), "b"), (key("0100"), "c"), (key("1000"), "d"), (key("00"), "e")]
+                .into_iter()
+                .collect();
+        let nearest: Vec<_> = map.nearest_by_xor(&key("0001"), 3).into_iter().map(|(_, v)| *v).collect();
+        assert_eq!(nearest, vec!["a", "b", "c"]);
+        assert!(map.nearest_by_xor(&key("0001"), 0).is_empty());
+        assert_eq!(map.nearest_by_xor(&key("11"), 5).len(), 1);
+
+        let close: Vec<_> = map.within_hamming(&key("0001"), 1).into_iter().map(|(_, v)| *v).collect();
+        assert_eq!(close, vec!["a", "b"]);
+        assert_eq!(map.within_hamming(&key("0001"), 2).len(), 4);
+        assert!(map.within_hamming(&key("00001"), 4).is_empty());
+    }
+
+
+    #[test]
+    fn set_operations_on_deep_chains() {
+        // 0, 00, 000 and so on up to 4096 bytes: each key nests inside the next, so the trees are
+        // as deep as they have keys
+        const BITS: usize = 4096 * 8;
+        // Built from the bottom up, since comparing each key with the last takes quadratic time
+        let chain = |parity: usize| {
+            let mut map = PatriciaMap::new();
+            for n in (1..=BITS).rev().filter(|n| n % 2 == parity) {
+                let mut node = LoopyPatriciaNode::new(&bits![u8, Msb0; 0, 0][..n.min(2)], Some(n));
+                node.left_child = map.root.take();
+                map.root = Some(Box::new(node));
+                map.length += 1;
+            }
+            map
+        };
+        let (odd, even) = (chain(1), chain(0));
+
+        let union = odd.union(&even);
+        assert_eq!(union.get_length(), BITS);
+        assert_eq!(union.get(&bitvec![u8, Msb0; 0; BITS]), Some(&BITS));
+        assert!(odd.is_subset(&union));
+        assert!(even.is_subset(&union));
+        assert!(!union.is_subset(&odd));
+        assert!(odd.is_disjoint(&even));
+        assert!(!union.is_disjoint(&even));
+        assert_eq!(union.difference(&odd).get_length(), BITS / 2);
+        assert!(union.intersection(&even).is_subset(&even));
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::collections::doubly_linked_list::{DoublyLinkedList, NodeRef};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+pub type Weigher<K, V> = Box<dyn Fn(&K, &V) -> usize>;
+pub type EvictionCallback<K, V> = Box<dyn FnMut(K, V)>;
+
+// Least-recently-used cache. `order` keeps the keys from least to most recently used and the map
+// holds a handle into it, so lookups, updates and evictions are all O(1).
+pub struct LruCache<K, V> {
+    map: HashMap<K, (V, NodeRef<K>)>,
+    order: DoublyLinkedList<K>,
+    capacity: usize,
+    weight: usize,
+    weigher: Weigher<K, V>,
+    on_evict: Option<EvictionCallback<K, V>>,
+    stats: CacheStats,
+}
+
+impl<K, V> LruCache<K, V> where K: Hash + Eq + Clone {
+    // Holds at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_weigher(capacity, Box::new(|_, _| 1))
+    }
+
+    // Holds entries until their total weight would exceed `capacity`.
+    pub fn with_weigher(capacity: usize, weigher: Weigher<K, V>) -> Self {
+        LruCache {
+            map: HashMap::new(),
+            order: DoublyLinkedList::new(),
+            capacity,
+            weight: 0,
+            weigher,
+            on_evict: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn set_eviction_callback(&mut self, callback: EvictionCallback<K, V>) {
+        self.on_evict = Some(callback);
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn get_weight(&self) -> usize {
+        self.weight
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    // Looks a value up without touching its recency or the statistics.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.map.get(key).map(|(value, _)| value)
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        match self.map.get_mut(key) {
+            Some((value, node)) => {
+                self.stats.hits += 1;
+                self.order.move_node_to_last(node).unwrap();
+                Some(value)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    // Returns the previous value for `key`. Entries heavier than the whole capacity are handed
+    // straight to the eviction callback instead of being cached.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let old = self.remove(&key);
+
+        let weight = (self.weigher)(&key, &value);
+        if weight > self.capacity {
+            self.evicted(key, value);
+            return old;
+        }
+
+        while self.weight + weight > self.capacity {
+            self.evict();
+        }
+
+        let node = self.order.push_last_node(key.clone());
+        self.map.insert(key, (value, node));
+        self.weight += weight;
+        old
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, node) = self.map.remove(key)?;
+        let key = self.order.remove_node(&node).unwrap();
+        self.weight -= (self.weigher)(&key, &value);
+        Some(value)
+    }
+
+    fn evict(&mut self) {
+        let key = self.order.get_first().unwrap();
+        let (value, _) = self.map.remove(&key).unwrap();
+        self.weight -= (self.weigher)(&key, &value);
+        self.evicted(key, value);
+    }
+
+    fn evicted(&mut self, key: K, value: V) {
+        self.stats.evictions += 1;
+        if let Some(callback) = self.on_evict.as_mut() {
+            callback(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        assert_eq!(cache.put(1, "one"), None);
+        assert_eq!(cache.put(2, "two"), None);
+        assert_eq!(cache.get(&1), Some(&"one"));
+
+        assert_eq!(cache.put(3, "three"), None);
+        assert!(!cache.contains(&2));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.get(&3), Some(&"three"));
+
+        assert_eq!(cache.put(1, "uno"), Some("one"));
+        assert_eq!(cache.put(4, "four"), None);
+        assert!(!cache.contains(&3));
+        assert_eq!(cache.peek(&1), Some(&"uno"));
+        assert_eq!(cache.get_length(), 2);
+
+        assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 1, evictions: 2 });
+    }
+
+    #[test]
+    fn peek_does_not_refresh() {
+        let mut cache = LruCache::new(2);
+        cache.put(1, 10);
+        cache.put(2, 20);
+        assert_eq!(cache.peek(&1), Some(&10));
+        cache.put(3, 30);
+        assert!(!cache.contains(&1));
+
+        if let Some(v) = cache.get_mut(&2) {
+            *v = 21;
+        }
+        cache.put(4, 40);
+        assert_eq!(cache.remove(&2), Some(21));
+        assert_eq!(cache.remove(&2), None);
+        assert_eq!(cache.get_length(), 1);
+    }
+
+    #[test]
+    fn weighted_capacity_and_callback() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let mut cache: LruCache<&str, String> = LruCache::with_weigher(10, Box::new(|_, v| v.len()));
+        let log = evicted.clone();
+        cache.set_eviction_callback(Box::new(move |k, _| log.borrow_mut().push(k)));
+
+        cache.put("a", "xxxx".to_string());
+        cache.put("b", "xxxx".to_string());
+        assert_eq!(cache.get_weight(), 8);
+        cache.get(&"a");
+
+        cache.put("c", "xxxxxx".to_string());
+        assert_eq!(*evicted.borrow(), vec!["b"]);
+        assert_eq!(cache.get_weight(), 10);
+
+        cache.put("huge", "x".repeat(11));
+        assert!(!cache.contains(&"huge"));
+        assert_eq!(*evicted.borrow(), vec!["b", "huge"]);
+
+        cache.put("c", "x".to_string());
+        assert_eq!(cache.get_weight(), 5);
+        assert_eq!(cache.stats().evictions, 2);
+    }
+}
@@ -0,0 +1,514 @@
+use bitvec::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use crate::collections::bit_prefix::matching_prefix_length;
+use crate::collections::loopy_patricia::{LoopyPatriciaNode, PatriciaMap};
+
+type Key = BitVec<u8, Msb0>;
+type KeySlice = BitSlice<u8, Msb0>;
+
+// The hash function of a Merkle tree. Implementations only need to turn bytes into a digest;
+// the tree decides what gets hashed.
+pub trait MerkleHasher {
+    type Hash: Copy + Eq + Debug + AsRef<[u8]> + for<'a> TryFrom<&'a [u8]>;
+
+    fn hash(data: &[u8]) -> Self::Hash;
+}
+
+// 64-bit FNV-1a. Fast and dependency free, but not collision resistant: it catches accidental
+// corruption, not a forger. Plug in a cryptographic hash for that.
+pub struct Fnv1aHasher;
+
+impl MerkleHasher for Fnv1aHasher {
+    type Hash = [u8; 8];
+
+    fn hash(data: &[u8]) -> Self::Hash {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for &byte in data {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash.to_be_bytes()
+    }
+}
+
+// Tags keep the different kinds of hashed input apart
+const VALUE_TAG: u8 = 0;
+const NODE_TAG: u8 = 1;
+
+fn hash_value<H: MerkleHasher>(value: &[u8]) -> H::Hash {
+    let mut data = Vec::with_capacity(value.len() + 1);
+    data.push(VALUE_TAG);
+    data.extend_from_slice(value);
+    H::hash(&data)
+}
+
+// A node commits to its segment, the hash of its value if it has one and the hashes of its
+// children, so the root hash commits to every key and value in the tree.
+fn hash_node<H: MerkleHasher>(segment: &KeySlice, value: Option<H::Hash>, children: [Option<H::Hash>; 2]) -> H::Hash {
+    let mut data = vec![NODE_TAG];
+    data.extend_from_slice(&(segment.len() as u64).to_le_bytes());
+    data.extend(segment.chunks(8).map(|byte| byte.load_be::<u8>()));
+    for hash in [value, children[0], children[1]] {
+        match hash {
+            Some(hash) => {
+                data.push(1);
+                data.extend_from_slice(hash.as_ref());
+            }
+            None => data.push(0),
+        }
+    }
+    H::hash(&data)
+}
+
+// A PatriciaMap with a hash over each subtree. The map already keeps every key set in one
+// compressed shape, so two maps with the same entries have the same root hash whatever order
+// they were built in. Hashes are cached on the side; changes clear the cached hashes on their
+// path and the next root_hash fills them in again.
+pub struct MerklePatriciaMap<V, H = Fnv1aHasher> where H: MerkleHasher {
+    map: PatriciaMap<V>,
+    // Keyed by node address. Nodes stay put while they live, and insert and remove forget every
+    // node the map might change or free before touching it, so no stale entry is ever read.
+    cache: RefCell<HashMap<usize, H::Hash>>,
+}
+
+impl<V, H> MerklePatriciaMap<V, H> where V: AsRef<[u8]>, H: MerkleHasher {
+    pub fn new() -> Self {
+        MerklePatriciaMap { map: PatriciaMap::new(), cache: RefCell::new(HashMap::new()) }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.map.get_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&V> {
+        self.map.get(key)
+    }
+
+    pub fn insert(&mut self, key: &Key, value: V) -> Option<V> {
+        self.forget_path(key, false);
+        self.map.insert(key, value)
+    }
+
+    pub fn remove(&mut self, key: &Key) -> Option<V> {
+        self.forget_path(key, true);
+        self.map.remove(key)
+    }
+
+    fn address(node: &LoopyPatriciaNode<V>) -> usize {
+        node as *const LoopyPatriciaNode<V> as usize
+    }
+
+    // Insert only changes the nodes on the way to `key`. Remove may also fold a child of the
+    // last two into its parent, which frees the child.
+    fn forget_path(&mut self, key: &Key, removing: bool) {
+        let cache = self.cache.get_mut();
+        let mut path = Vec::new();
+        let mut next = self.map.root.as_deref();
+        let mut consumed = 0;
+        while let Some(node) = next {
+            path.push(node);
+            cache.remove(&Self::address(node));
+            let rest = &key[consumed..];
+            if matching_prefix_length(&node.key, rest) != node.key.len() || node.key.len() == rest.len() {
+                break;
+            }
+            consumed += node.key.len();
+            let child = if key[consumed] { &node.right_child } else { &node.left_child };
+            next = child.as_deref();
+        }
+        if removing {
+            for node in path.iter().rev().take(2) {
+                for child in [&node.left_child, &node.right_child].into_iter().flatten() {
+                    cache.remove(&Self::address(child));
+                }
+            }
+        }
+    }
+
+    // The hash of the empty input stands for the empty tree
+    pub fn root_hash(&self) -> H::Hash {
+        match &self.map.root {
+            Some(root) => self.digest(root),
+            None => H::hash(&[]),
+        }
+    }
+
+    fn digest(&self, node: &LoopyPatriciaNode<V>) -> H::Hash {
+        let address = Self::address(node);
+        if let Some(&hash) = self.cache.borrow().get(&address) {
+            return hash;
+        }
+        let hash = hash_node::<H>(&node.key, Self::value_hash(node), self.child_hashes(node));
+        self.cache.borrow_mut().insert(address, hash);
+        hash
+    }
+
+    fn value_hash(node: &LoopyPatriciaNode<V>) -> Option<H::Hash> {
+        node.value.as_ref().map(|value| hash_value::<H>(value.as_ref()))
+    }
+
+    fn child_hashes(&self, node: &LoopyPatriciaNode<V>) -> [Option<H::Hash>; 2] {
+        [&node.left_child, &node.right_child].map(|child| child.as_deref().map(|child| self.digest(child)))
+    }
+
+    // The nodes on the way to `key`, enough to check its value, or its absence, against the
+    // root hash
+    pub fn prove(&self, key: &Key) -> MerkleProof<H> {
+        let mut proof = MerkleProof { nodes: Vec::new() };
+        let mut next = self.map.root.as_deref();
+        let mut consumed = 0;
+        while let Some(node) = next {
+            proof.nodes.push(ProofNode {
+                segment: node.key.clone(),
+                value: Self::value_hash(node),
+                children: self.child_hashes(node),
+            });
+            let rest = &key[consumed..];
+            if matching_prefix_length(&node.key, rest) != node.key.len() || node.key.len() == rest.len() {
+                break;
+            }
+            consumed += node.key.len();
+            let child = if key[consumed] { &node.right_child } else { &node.left_child };
+            next = child.as_deref();
+        }
+        proof
+    }
+}
+
+pub struct ProofNode<H: MerkleHasher> {
+    segment: Key,
+    value: Option<H::Hash>,
+    children: [Option<H::Hash>; 2],
+}
+
+pub struct MerkleProof<H: MerkleHasher> {
+    // From the root down
+    nodes: Vec<ProofNode<H>>,
+}
+
+// Proof encoding, integers little-endian:
+//   header  hash length u8, node count u32
+//   nodes   segment length u64, segment bits zero padded to whole bytes, a flags byte with
+//           bit 0 for a value and bits 1 and 2 for the left and right child, then the value
+//           and child hashes that are present, in that order
+impl<H> MerkleProof<H> where H: MerkleHasher {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let hash_length = H::hash(&[]).as_ref().len();
+        let mut bytes = vec![hash_length as u8];
+        bytes.extend_from_slice(&(self.nodes.len() as u32).to_le_bytes());
+        for node in &self.nodes {
+            bytes.extend_from_slice(&(node.segment.len() as u64).to_le_bytes());
+            let mut segment = node.segment.clone();
+            segment.set_uninitialized(false);
+            bytes.extend_from_slice(segment.as_raw_slice());
+            let hashes = [node.value, node.children[0], node.children[1]];
+            let flags = hashes.iter().enumerate().fold(0u8, |flags, (i, hash)| flags | ((hash.is_some() as u8) << i));
+            bytes.push(flags);
+            for hash in hashes.iter().flatten() {
+                bytes.extend_from_slice(hash.as_ref());
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ()> {
+        let mut rest = bytes;
+        let mut take = |count: usize| -> Result<&[u8], ()> {
+            if rest.len() < count {
+                return Err(());
+            }
+            let (taken, left) = rest.split_at(count);
+            rest = left;
+            Ok(taken)
+        };
+
+        let hash_length = take(1)?[0] as usize;
+        if hash_length != H::hash(&[]).as_ref().len() {
+            return Err(());
+        }
+        let count = u32::from_le_bytes(take(4)?.try_into().unwrap());
+        let mut nodes = Vec::new();
+        for _ in 0..count {
+            let length = u64::from_le_bytes(take(8)?.try_into().unwrap()) as usize;
+            let mut segment = Key::from_slice(take(length.div_ceil(8))?);
+            segment.truncate(length);
+            let flags = take(1)?[0];
+            if flags >> 3 != 0 {
+                return Err(());
+            }
+            let mut hashes = [None; 3];
+            for (i, hash) in hashes.iter_mut().enumerate() {
+                if flags & (1 << i) != 0 {
+                    *hash = Some(H::Hash::try_from(take(hash_length)?).map_err(|_| ())?);
+                }
+            }
+            nodes.push(ProofNode { segment, value: hashes[0], children: [hashes[1], hashes[2]] });
+        }
+        if !rest.is_empty() {
+            return Err(());
+        }
+        Ok(MerkleProof { nodes })
+    }
+
+    // Checks that, in the tree with this root hash, `key` maps to `value`, or is missing when
+    // `value` is None
+    pub fn verify(&self, root_hash: H::Hash, key: &KeySlice, value: Option<&[u8]>) -> bool {
+        if self.nodes.is_empty() {
+            return value.is_none() && root_hash == H::hash(&[]);
+        }
+
+        let mut expected = root_hash;
+        let mut consumed = 0;
+        for (i, node) in self.nodes.iter().enumerate() {
+            let last = i == self.nodes.len() - 1;
+            if hash_node::<H>(&node.segment, node.value, node.children) != expected {
+                return false;
+            }
+            let rest = &key[consumed..];
+            if matching_prefix_length(&node.segment, rest) != node.segment.len() {
+                // The key leaves the tree inside this segment
+                return last && value.is_none();
+            }
+            consumed += node.segment.len();
+            if consumed == key.len() {
+                return last && match (value, node.value) {
+                    (Some(value), Some(hash)) => hash_value::<H>(value) == hash,
+                    (None, None) => true,
+                    _ => false,
+                };
+            }
+            match node.children[key[consumed] as usize] {
+                Some(hash) if !last => expected = hash,
+                Some(_) => return false,
+                None => return last && value.is_none(),
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+    use std::collections::HashMap;
+    use std::hash::{DefaultHasher, Hasher};
+
+    fn random_key(rng: &mut impl Rng) -> Key {
+        let length = rng.random_range(0..14);
+        (0..length).map(|_| rng.random_bool(0.5)).collect()
+    }
+
+    // Every node either holds a value or branches both ways, and any cached hash is current.
+    // Returns the node's hash worked out from scratch and counts the cached ones in `found`.
+    fn assert_canonical<V: AsRef<[u8]>>(cache: &HashMap<usize, [u8; 8]>, node: &LoopyPatriciaNode<V>, found: &mut usize) -> [u8; 8] {
+        let children = [&node.left_child, &node.right_child];
+        assert!(node.value.is_some() || children.iter().all(|c| c.is_some()));
+        let value = node.value.as_ref().map(|value| hash_value::<Fnv1aHasher>(value.as_ref()));
+        let hash = hash_node::<Fnv1aHasher>(&node.key, value, children.map(|c| c.as_deref().map(|c| assert_canonical(cache, c, found))));
+        if let Some(cached) = cache.get(&MerklePatriciaMap::<V>::address(node)) {
+            assert_eq!(*cached, hash);
+            *found += 1;
+        }
+        hash
+    }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map: MerklePatriciaMap<Vec<u8>> = MerklePatriciaMap::new();
+        let empty = map.root_hash();
+        let a = bits![u8, Msb0; 1, 0, 1].to_bitvec();
+        let b = bits![u8, Msb0; 1, 0].to_bitvec();
+        assert_eq!(map.insert(&a, b"a".to_vec()), None);
+        let only_a = map.root_hash();
+        assert_eq!(map.insert(&b, b"b".to_vec()), None);
+        assert_eq!(map.insert(&a, b"c".to_vec()), Some(b"a".to_vec()));
+        assert_eq!(map.get_length(), 2);
+        assert_eq!(map.get(&a), Some(&b"c".to_vec()));
+        assert_eq!(map.get(&bits![u8, Msb0; 1].to_bitvec()), None);
+
+        assert_eq!(map.remove(&b), Some(b"b".to_vec()));
+        assert_eq!(map.remove(&b), None);
+        assert_ne!(map.root_hash(), only_a);
+        map.insert(&a, b"a".to_vec());
+        assert_eq!(map.root_hash(), only_a);
+        map.remove(&a);
+        assert!(map.is_empty());
+        assert_eq!(map.root_hash(), empty);
+    }
+
+    #[test]
+    fn root_hash_ignores_insertion_order() {
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            let mut entries: Vec<(Key, Vec<u8>)> = (0..rng.random_range(0..100))
+                .map(|_| (random_key(&mut rng), vec![rng.random()]))
+                .collect::<HashMap<_, _>>()
+                .into_iter()
+                .collect();
+            let mut first: MerklePatriciaMap<Vec<u8>> = MerklePatriciaMap::new();
+            for (key, value) in &entries {
+                first.insert(key, value.clone());
+            }
+            // The second map also sees keys that come and go again
+            entries.shuffle(&mut rng);
+            let mut second: MerklePatriciaMap<Vec<u8>> = MerklePatriciaMap::new();
+            for (key, value) in &entries {
+                let extra = random_key(&mut rng);
+                let added = second.get(&extra).is_none() && !entries.iter().any(|(k, _)| *k == extra);
+                if added {
+                    second.insert(&extra, vec![]);
+                }
+                second.root_hash();
+                second.insert(key, value.clone());
+                if added {
+                    second.root_hash();
+                    second.remove(&extra);
+                }
+                // Nothing is cached for nodes that have gone away
+                let mut found = 0;
+                if let Some(root) = &second.map.root {
+                    assert_canonical(&second.cache.borrow(), root, &mut found);
+                }
+                assert_eq!(found, second.cache.borrow().len());
+            }
+            assert_eq!(first.root_hash(), second.root_hash());
+            assert_eq!(first.get_length(), second.get_length());
+        }
+    }
+
+    #[test]
+    fn random_proofs() {
+        let mut rng = rand::rng();
+        let mut map: MerklePatriciaMap<Vec<u8>> = MerklePatriciaMap::new();
+        let mut oracle = HashMap::new();
+        for _ in 0..300 {
+            let key = random_key(&mut rng);
+            let value = vec![rng.random(), rng.random()];
+            map.insert(&key, value.clone());
+            oracle.insert(key, value);
+        }
+        let root = map.root_hash();
+
+        for _ in 0..1000 {
+            let key = random_key(&mut rng);
+            let proof = map.prove(&key);
+            match oracle.get(&key) {
+                Some(value) => {
+                    assert!(proof.verify(root, &key, Some(value.as_slice())));
+                    assert!(!proof.verify(root, &key, Some(&b"forged"[..])));
+                    assert!(!proof.verify(root, &key, None));
+                }
+                None => {
+                    assert!(proof.verify(root, &key, None));
+                    assert!(!proof.verify(root, &key, Some(&b"forged"[..])));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tampered_proofs_fail() {
+        let mut map: MerklePatriciaMap<&str> = MerklePatriciaMap::new();
+        let keys: Vec<Key> = ["0", "01", "011", "1", "110"]
+            .iter()
+            .map(|text| text.chars().map(|c| c == '1').collect())
+            .collect();
+        for key in &keys {
+            map.insert(key, "value");
+        }
+        let root = map.root_hash();
+        let key = &keys[2];
+
+        let mut proof = map.prove(key);
+        assert!(proof.verify(root, key, Some(&b"value"[..])));
+        proof.nodes.pop();
+        assert!(!proof.verify(root, key, Some(&b"value"[..])));
+        // A truncated path must not pass as proof of absence either
+        assert!(!proof.verify(root, key, None));
+
+        let mut proof = map.prove(key);
+        proof.nodes[0].children.swap(0, 1);
+        assert!(!proof.verify(root, key, Some(&b"value"[..])));
+
+        let proof = map.prove(key);
+        map.insert(key, "changed");
+        assert!(!proof.verify(map.root_hash(), key, Some(&b"value"[..])));
+        assert!(map.prove(key).verify(map.root_hash(), key, Some(&b"changed"[..])));
+
+        let empty: MerklePatriciaMap<&str> = MerklePatriciaMap::new();
+        assert!(empty.prove(key).verify(empty.root_hash(), key, None));
+        assert!(!empty.prove(key).verify(root, key, None));
+    }
+
+    struct SipHasher;
+
+    impl MerkleHasher for SipHasher {
+        type Hash = [u8; 8];
+
+        fn hash(data: &[u8]) -> Self::Hash {
+            let mut hasher = DefaultHasher::new();
+            hasher.write(data);
+            hasher.finish().to_le_bytes()
+        }
+    }
+
+    #[test]
+    fn pluggable_hasher() {
+        let mut fnv: MerklePatriciaMap<&str> = MerklePatriciaMap::new();
+        let mut sip: MerklePatriciaMap<&str, SipHasher> = MerklePatriciaMap::new();
+        let key = bits![u8, Msb0; 0, 1, 1].to_bitvec();
+        let missing = bits![u8, Msb0; 0].to_bitvec();
+        fnv.insert(&key, "x");
+        sip.insert(&key, "x");
+        assert_ne!(fnv.root_hash(), sip.root_hash());
+        assert!(sip.prove(&key).verify(sip.root_hash(), &key, Some(&b"x"[..])));
+        assert!(sip.prove(&missing).verify(sip.root_hash(), &missing, None));
+    }
+
+    struct WideHasher;
+
+    impl MerkleHasher for WideHasher {
+        type Hash = [u8; 16];
+
+        fn hash(data: &[u8]) -> Self::Hash {
+            let mut hash = [0; 16];
+            hash[..8].copy_from_slice(&Fnv1aHasher::hash(data));
+            hash[8..].copy_from_slice(&SipHasher::hash(data));
+            hash
+        }
+    }
+
+    #[test]
+    fn proofs_survive_encoding() {
+        let mut rng = rand::rng();
+        let mut map: MerklePatriciaMap<Vec<u8>> = MerklePatriciaMap::new();
+        for _ in 0..200 {
+            map.insert(&random_key(&mut rng), vec![rng.random()]);
+        }
+        let root = map.root_hash();
+        for _ in 0..200 {
+            let key = random_key(&mut rng);
+            let value = map.get(&key).map(|value| value.as_slice());
+            let bytes = map.prove(&key).to_bytes();
+            let decoded = MerkleProof::<Fnv1aHasher>::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.to_bytes(), bytes);
+            assert!(decoded.verify(root, &key, value));
+
+            assert!(MerkleProof::<Fnv1aHasher>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+            let mut longer = bytes.clone();
+            longer.push(0);
+            assert!(MerkleProof::<Fnv1aHasher>::from_bytes(&longer).is_err());
+            // The hash length in the header has to match the verifier's hasher
+            assert!(MerkleProof::<WideHasher>::from_bytes(&bytes).is_err());
+        }
+    }
+}
@@ -1,4 +1,24 @@
 pub mod linked_list;
 pub mod doubly_linked_list;
+pub mod persistent_list;
+pub mod stack;
+pub mod queue;
+pub mod deque;
+pub mod ring_buffer;
+pub mod lru_cache;
+pub mod lfu_cache;
+pub mod skip_list;
+pub mod unrolled_list;
+pub mod arena_list;
+pub(crate) mod hazard_pointer;
+pub mod concurrent_stack;
+pub mod concurrent_queue;
+pub(crate) mod bit_prefix;
 pub(crate) mod patricia;
 pub mod loopy_patricia;
+pub mod ip_routing_table;
+pub mod patricia_key;
+pub mod bit_trie;
+pub mod compact_patricia;
+pub mod patricia_file;
+pub mod merkle_patricia;
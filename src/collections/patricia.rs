@@ -1,5 +1,6 @@
 use bitvec::prelude::*;
 use std::mem::swap;
+use crate::collections::bit_prefix::matching_prefix_length;
 
 
 type Key = BitVec<u8, Msb0>;
@@ -29,28 +30,39 @@ impl PatriciaNode {
 #[derive(Debug)]
 pub(crate) struct PatriciaTree {
     root: PatriciaNode,
+    length: usize,
 }
 
 impl PatriciaTree {
     pub fn new() -> Self {
         PatriciaTree {
             root: PatriciaNode::new(&BitVec::new().as_bitslice(), false),
+            length: 0,
         }
     }
 
-    pub fn insert(&mut self, key: &Key) {
-        Self::find_and_insert(&mut self.root, key);
+    pub fn get_length(&self) -> usize {
+        self.length
     }
 
-    fn find_and_insert(node: &mut PatriciaNode, key: &Key) {
-        let i = Self::find_number_of_matching_bits(&node.key, &key);
+    // Returns false if the key was already in the tree
+    pub fn insert(&mut self, key: &Key) -> bool {
+        let added = Self::find_and_insert(&mut self.root, key);
+        if added {
+            self.length += 1;
+        }
+        added
+    }
+
+    fn find_and_insert(node: &mut PatriciaNode, key: &KeySlice) -> bool {
+        let i = Self::find_number_of_matching_bits(&node.key, key);
 
         let (base_segment, new_segment) = key.split_at(i);
         let (_, old_segment) = node.key.split_at(i);
 
         if i == key.len() {
-            if i!= 0 && i != node.key.len() {
-                let mut new_child = PatriciaNode::new(&old_segment.to_bitvec(), node.end);
+            if i != node.key.len() {
+                let mut new_child = PatriciaNode::new(old_segment, node.end);
                 swap(&mut new_child.left_child, &mut node.left_child);
                 swap(&mut new_child.right_child, &mut node.right_child);
                 if old_segment[0] {
@@ -59,23 +71,25 @@ impl PatriciaTree {
                     node.left_child = Some(Box::new(new_child));
                 }
                 node.key.truncate(i);
-                node.end = true;
+                node.end = false;
             }
-            return;
+            let added = !node.end;
+            node.end = true;
+            return added;
         }
 
         if i == node.key.len() {
             if key[i] {
                 if node.right_child.is_some() {
-                    Self::find_and_insert(node.right_child.as_mut().unwrap(), &new_segment.to_bitvec());
+                    return Self::find_and_insert(node.right_child.as_mut().unwrap(), new_segment);
                 } else {
-                    node.right_child = Some(Box::new(PatriciaNode::new(&new_segment.to_bitvec(), true)));
+                    node.right_child = Some(Box::new(PatriciaNode::new(new_segment, true)));
                 }
             } else {
                 if node.left_child.is_some() {
-                    Self::find_and_insert(node.left_child.as_mut().unwrap(), &new_segment.to_bitvec());
+                    return Self::find_and_insert(node.left_child.as_mut().unwrap(), new_segment);
                 } else {
-                    node.left_child = Some(Box::new(PatriciaNode::new(&new_segment.to_bitvec(), true)));
+                    node.left_child = Some(Box::new(PatriciaNode::new(new_segment, true)));
                 }
             }
         } else {
@@ -83,15 +97,15 @@ impl PatriciaTree {
             let mut new_left_node;
 
             if key[i] == false {
-                new_left_node = PatriciaNode::new(&new_segment.to_bitvec(), true);
-                new_right_node = PatriciaNode::new(&old_segment.to_bitvec(), node.end);
+                new_left_node = PatriciaNode::new(new_segment, true);
+                new_right_node = PatriciaNode::new(old_segment, node.end);
                 swap(&mut new_right_node.left_child, &mut node.left_child);
                 swap(&mut new_right_node.right_child, &mut node.right_child);
             } else {
-                new_left_node = PatriciaNode::new(&old_segment.to_bitvec(), node.end);
+                new_left_node = PatriciaNode::new(old_segment, node.end);
                 swap(&mut new_left_node.left_child, &mut node.left_child);
                 swap(&mut new_left_node.right_child, &mut node.right_child);
-                new_right_node = PatriciaNode::new(&new_segment.to_bitvec(), true);
+                new_right_node = PatriciaNode::new(new_segment, true);
             }
             node.right_child = Some(Box::new(new_right_node));
             node.left_child = Some(Box::new(new_left_node));
@@ -99,16 +113,11 @@ impl PatriciaTree {
             node.key.extend_from_bitslice(base_segment);
             node.end = false;
         }
+        true
     }
 
-    fn find_number_of_matching_bits(src: &Key, dest: &Key) -> usize {
-        let length = src.len().min(dest.len());
-        for i in 0..length {
-            if src[i] != dest[i] {
-                return i;
-            }
-        }
-        return length;
+    fn find_number_of_matching_bits(src: &KeySlice, dest: &KeySlice) -> usize {
+        matching_prefix_length(src, dest)
     }
 
     // Search for a key in the tree
@@ -117,8 +126,11 @@ impl PatriciaTree {
     }
 
     // Search for a key in the tree
-    fn search_internal(node: &PatriciaNode, key: &Key) -> bool {
+    fn search_internal(node: &PatriciaNode, key: &KeySlice) -> bool {
         let i = Self::find_number_of_matching_bits(&node.key, key);
+        if i != node.key.len() {
+            return false;
+        }
         if i == key.len() {
             return node.end
         }
@@ -131,23 +143,109 @@ impl PatriciaTree {
             if node.right_child.is_none() {
                 return false;
             }
-            Self::search_internal(node.right_child.as_ref().unwrap(), &right.to_bitvec())
+            Self::search_internal(node.right_child.as_ref().unwrap(), right)
         } else {
             if node.left_child.is_none() {
                 return false;
             }
-            Self::search_internal(node.left_child.as_ref().unwrap(), &right.to_bitvec())
+            Self::search_internal(node.left_child.as_ref().unwrap(), right)
+        }
+    }
+
+    // Remove a key from the tree, returns false if it was not there
+    pub fn remove(&mut self, key: &Key) -> bool {
+        let removed = Self::remove_internal(&mut self.root, key);
+        if removed {
+            self.length -= 1;
         }
+        removed
     }
 
-    // fn delete(&mut self, _key: &BitVec) {
-    //     unimplemented!(); // Implement delete method if needed
-    // }
+    fn remove_internal(node: &mut PatriciaNode, key: &KeySlice) -> bool {
+        let i = Self::find_number_of_matching_bits(&node.key, key);
+        if i != node.key.len() {
+            return false;
+        }
+        if i == key.len() {
+            let removed = node.end;
+            node.end = false;
+            return removed;
+        }
+
+        let (_, right) = key.split_at(i);
+        let child = if right[0] { &mut node.right_child } else { &mut node.left_child };
+        let removed = match child {
+            Some(child) => Self::remove_internal(child, right),
+            None => false,
+        };
+        if removed {
+            Self::compress(child);
+        }
+        removed
+    }
+
+    // Drop a node that no longer holds a key or leads to one, and fold a node with a single child
+    // into that child. The root is never compressed so that its key stays empty.
+    fn compress(link: &mut Option<Box<PatriciaNode>>) {
+        let node = match link {
+            Some(node) if !node.end => node,
+            _ => return,
+        };
+        match (node.left_child.take(), node.right_child.take()) {
+            (None, None) => *link = None,
+            (Some(only), None) | (None, Some(only)) => {
+                let only = *only;
+                node.key.extend_from_bitslice(&only.key);
+                node.end = only.end;
+                node.left_child = only.left_child;
+                node.right_child = only.right_child;
+            }
+            (left, right) => {
+                node.left_child = left;
+                node.right_child = right;
+            }
+        }
+    }
+
+    // Keys in order, a key before its extensions and 0 before 1
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            stack: vec![(&self.root, 0)],
+            key: Key::new(),
+        }
+    }
+}
+
+pub(crate) struct Iter<'a> {
+    stack: Vec<(&'a PatriciaNode, usize)>,
+    key: Key,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Key;
+
+    fn next(&mut self) -> Option<Key> {
+        while let Some((node, depth)) = self.stack.pop() {
+            self.key.truncate(depth);
+            self.key.extend_from_bitslice(&node.key);
+            let depth = self.key.len();
+            if let Some(right) = &node.right_child {
+                self.stack.push((right, depth));
+            }
+            if let Some(left) = &node.left_child {
+                self.stack.push((left, depth));
+            }
+            if node.end {
+                return Some(self.key.clone());
+            }
+        }
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use rand::RngCore;
+    use rand::{Rng, RngCore};
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
 
@@ -192,5 +290,80 @@ mod tests {
             println!("{}", text);
         }
     }
-}
 
+
+    fn short_key(rng: &mut impl Rng) -> Key {
+        let length = rng.random_range(0..12);
+        (0..length).map(|_| rng.random_bool(0.5)).collect()
+    }
+
+    #[test]
+    fn prefixes_and_remove() {
+        let mut p = PatriciaTree::new();
+        let keys: Vec<Key> = [&[][..], &[true], &[true, false, true], &[true, false], &[false, false]]
+            .iter()
+            .map(|bits| bits.iter().collect())
+            .collect();
+        for key in &keys[1..] {
+            p.insert(key);
+        }
+        assert!(!p.search(&keys[0]));
+        assert!(!p.search(&bits![u8, Msb0; 1, 0, 1, 1].to_bitvec()));
+        assert!(!p.search(&bits![u8, Msb0; 0].to_bitvec()));
+        p.insert(&keys[0]);
+        for key in &keys {
+            assert!(p.search(key));
+        }
+
+        assert!(p.remove(&keys[3]));
+        assert!(!p.remove(&keys[3]));
+        assert!(!p.search(&keys[3]));
+        assert!(p.search(&keys[2]));
+        // 1 and 101 are left under the right branch, 10 has been folded into its child.
+        let right = p.root.right_child.as_ref().unwrap();
+        assert_eq!(right.key, bits![u8, Msb0; 1]);
+        assert_eq!(right.left_child.as_ref().unwrap().key, bits![u8, Msb0; 0, 1]);
+
+        for key in &keys {
+            p.remove(key);
+        }
+        assert!(p.root.left_child.is_none() && p.root.right_child.is_none());
+        assert!(!p.root.end);
+    }
+
+    // Every node below the root either holds a key or branches both ways.
+    fn assert_compressed(node: &PatriciaNode, is_root: bool) {
+        let children = [&node.left_child, &node.right_child];
+        if !is_root {
+            assert!(node.end || children.iter().all(|c| c.is_some()));
+        }
+        for child in children.into_iter().flatten() {
+            assert_compressed(child, false);
+        }
+    }
+
+    #[test]
+    fn random_insert_remove_against_hash_set() {
+        let mut rng = rand::rng();
+        let mut p = PatriciaTree::new();
+        let mut oracle = std::collections::HashSet::new();
+
+        for _ in 0..5000 {
+            let key = short_key(&mut rng);
+            if rng.random_bool(0.5) {
+                p.insert(&key);
+                oracle.insert(key);
+            } else {
+                assert_eq!(p.remove(&key), oracle.remove(&key));
+            }
+            let probe = short_key(&mut rng);
+            assert_eq!(p.search(&probe), oracle.contains(&probe));
+            assert_compressed(&p.root, true);
+        }
+        for key in &oracle {
+            assert!(p.search(key));
+            assert!(p.remove(key));
+        }
+        assert!(p.root.left_child.is_none() && p.root.right_child.is_none());
+    }
+}
@@ -0,0 +1,483 @@
+use bitvec::prelude::*;
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::collections::bit_prefix::matching_prefix_length;
+use crate::collections::compact_patricia::{CompactNode, CompactPatriciaTree, NIL};
+use crate::collections::loopy_patricia::LoopyPatriciaTree;
+
+type Key = BitVec<u8, Msb0>;
+type KeySlice = BitSlice<u8, Msb0>;
+
+// File layout, all integers little-endian:
+//   header  magic "PATR", version u16, reserved u16, root u32, node count u32,
+//           key count u64, bit count u64
+//   nodes   left u32, right u32, offset u64, length u32, end u8 for each node
+//   pool    the key bits, most significant bit of each byte first, zero padded
+//   trailer CRC-32 of everything before it
+// Nodes are numbered in pre-order, so every child has a larger index than its parent.
+const MAGIC: [u8; 4] = *b"PATR";
+const VERSION: u16 = 1;
+const HEADER_SIZE: usize = 32;
+const NODE_SIZE: usize = 21;
+const CHECKSUM_SIZE: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatriciaFormatError {
+    BadMagic,
+    UnsupportedVersion(u16),
+    Truncated,
+    ChecksumMismatch,
+    // The checksum matched but the contents do not describe a valid tree
+    Corrupt,
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+// CRC-32 as used by zip and PNG
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc = CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+impl CompactPatriciaTree {
+    // Writes the live nodes only, renumbered, with their segments packed into a fresh pool
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (nodes, pool) = self.repacked();
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + nodes.len() * NODE_SIZE);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0u16.to_le_bytes());
+        bytes.extend_from_slice(&(if nodes.is_empty() { NIL } else { 0 }).to_le_bytes());
+        bytes.extend_from_slice(&(nodes.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.length as u64).to_le_bytes());
+        bytes.extend_from_slice(&(pool.len() as u64).to_le_bytes());
+
+        for node in &nodes {
+            for child in node.children {
+                bytes.extend_from_slice(&child.to_le_bytes());
+            }
+            bytes.extend_from_slice(&(node.offset as u64).to_le_bytes());
+            bytes.extend_from_slice(&node.length.to_le_bytes());
+            bytes.push(node.end as u8);
+        }
+
+        bytes.extend_from_slice(pool.as_raw_slice());
+        let checksum = crc32(&bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    // Copies a validated file back into a tree that can be modified again
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PatriciaFormatError> {
+        let view = PatriciaView::new(bytes)?;
+        let nodes: Vec<CompactNode> = (0..view.node_count).map(|index| view.node(index)).collect();
+        let live: usize = nodes.iter().map(|node| node.length as usize).sum();
+        Ok(CompactPatriciaTree {
+            garbage: view.bits.len().saturating_sub(live),
+            nodes,
+            free: Vec::new(),
+            bits: view.bits.to_bitvec(),
+            root: view.root,
+            length: view.length,
+        })
+    }
+
+    pub fn save<P>(&self, path: P) -> io::Result<()> where P: AsRef<Path> {
+        fs::write(path, self.to_bytes())
+    }
+}
+
+// PatriciaSet is stored in the same format, by way of the compact layout
+impl LoopyPatriciaTree {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        CompactPatriciaTree::from(self).to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PatriciaFormatError> {
+        let view = PatriciaView::new(bytes)?;
+        LoopyPatriciaTree::from_sorted_iter(view.iter()).map_err(|_| PatriciaFormatError::Corrupt)
+    }
+
+    pub fn save<P>(&self, path: P) -> io::Result<()> where P: AsRef<Path> {
+        fs::write(path, self.to_bytes())
+    }
+}
+
+// A read-only tree that works straight off the bytes of a file, for example a memory-mapped
+// one. Everything is checked once up front, so lookups cannot go out of bounds later.
+pub struct PatriciaView<'a> {
+    nodes: &'a [u8],
+    bits: &'a KeySlice,
+    root: u32,
+    node_count: u32,
+    length: usize,
+}
+
+impl<'a> PatriciaView<'a> {
+    pub fn new(bytes: &'a [u8]) -> Result<Self, PatriciaFormatError> {
+        if bytes.len() < HEADER_SIZE + CHECKSUM_SIZE {
+            return Err(PatriciaFormatError::Truncated);
+        }
+        if bytes[0..4] != MAGIC {
+            return Err(PatriciaFormatError::BadMagic);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(PatriciaFormatError::UnsupportedVersion(version));
+        }
+
+        let root = read_u32(bytes, 8);
+        let node_count = read_u32(bytes, 12);
+        let length = read_u64(bytes, 16) as usize;
+        let bit_count = read_u64(bytes, 24) as usize;
+        let nodes_end = (node_count as usize)
+            .checked_mul(NODE_SIZE)
+            .and_then(|size| size.checked_add(HEADER_SIZE))
+            .ok_or(PatriciaFormatError::Corrupt)?;
+        let expected = nodes_end
+            .checked_add(bit_count.div_ceil(8))
+            .and_then(|size| size.checked_add(CHECKSUM_SIZE))
+            .ok_or(PatriciaFormatError::Corrupt)?;
+        if bytes.len() < expected {
+            return Err(PatriciaFormatError::Truncated);
+        }
+        if bytes.len() > expected {
+            return Err(PatriciaFormatError::Corrupt);
+        }
+
+        let body = &bytes[..bytes.len() - CHECKSUM_SIZE];
+        if crc32(body) != read_u32(bytes, body.len()) {
+            return Err(PatriciaFormatError::ChecksumMismatch);
+        }
+
+        let view = PatriciaView {
+            nodes: &bytes[HEADER_SIZE..nodes_end],
+            bits: &bytes[nodes_end..body.len()].view_bits::<Msb0>()[..bit_count],
+            root,
+            node_count,
+            length,
+        };
+        view.validate()?;
+        Ok(view)
+    }
+
+    fn validate(&self) -> Result<(), PatriciaFormatError> {
+        let expected_root = if self.node_count == 0 { NIL } else { 0 };
+        if self.root != expected_root {
+            return Err(PatriciaFormatError::Corrupt);
+        }
+
+        let mut keys = 0;
+        // Every node but the root needs exactly one parent; with children always after their
+        // parent that also makes every node reachable from the root
+        let mut has_parent = vec![false; self.node_count as usize];
+        for index in 0..self.node_count {
+            let node = self.node(index);
+            match node.offset.checked_add(node.length as usize) {
+                Some(end) if end <= self.bits.len() => {}
+                _ => return Err(PatriciaFormatError::Corrupt),
+            }
+            for (bit, child) in node.children.iter().enumerate() {
+                if *child == NIL {
+                    continue;
+                }
+                // Children come later in pre-order, which also rules out cycles
+                if *child <= index || *child >= self.node_count {
+                    return Err(PatriciaFormatError::Corrupt);
+                }
+                if has_parent[*child as usize] {
+                    return Err(PatriciaFormatError::Corrupt);
+                }
+                has_parent[*child as usize] = true;
+                let child = self.node(*child);
+                if child.length == 0 || child.offset >= self.bits.len() || self.bits[child.offset] != (bit == 1) {
+                    return Err(PatriciaFormatError::Corrupt);
+                }
+            }
+            // A leaf must hold a key
+            if !node.end && node.children == [NIL, NIL] {
+                return Err(PatriciaFormatError::Corrupt);
+            }
+            keys += node.end as usize;
+        }
+        if has_parent.iter().skip(1).any(|has_parent| !has_parent) || keys != self.length {
+            return Err(PatriciaFormatError::Corrupt);
+        }
+        Ok(())
+    }
+
+    fn node(&self, index: u32) -> CompactNode {
+        let at = index as usize * NODE_SIZE;
+        CompactNode {
+            children: [read_u32(self.nodes, at), read_u32(self.nodes, at + 4)],
+            offset: read_u64(self.nodes, at + 8) as usize,
+            length: read_u32(self.nodes, at + 16),
+            end: self.nodes[at + 20] != 0,
+        }
+    }
+
+    fn segment(&self, node: &CompactNode) -> &'a KeySlice {
+        &self.bits[node.offset..node.offset + node.length as usize]
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn search(&self, key: &KeySlice) -> bool {
+        if self.root == NIL {
+            return false;
+        }
+        let mut node = self.node(self.root);
+        let mut key = key;
+        loop {
+            let segment = self.segment(&node);
+            let i = matching_prefix_length(segment, key);
+            if i != segment.len() {
+                return false;
+            }
+            if i == key.len() {
+                return node.end;
+            }
+            let child = node.children[key[i] as usize];
+            if child == NIL {
+                return false;
+            }
+            node = self.node(child);
+            key = &key[i..];
+        }
+    }
+
+    // Keys in order, a key before its extensions and 0 before 1
+    pub fn iter(&self) -> ViewIter<'_, 'a> {
+        ViewIter {
+            view: self,
+            stack: if self.root == NIL { Vec::new() } else { vec![(self.root, 0)] },
+            key: Key::new(),
+        }
+    }
+}
+
+pub struct ViewIter<'v, 'a> {
+    view: &'v PatriciaView<'a>,
+    stack: Vec<(u32, usize)>,
+    key: Key,
+}
+
+impl Iterator for ViewIter<'_, '_> {
+    type Item = Key;
+
+    fn next(&mut self) -> Option<Key> {
+        while let Some((index, depth)) = self.stack.pop() {
+            let node = self.view.node(index);
+            self.key.truncate(depth);
+            self.key.extend_from_bitslice(self.view.segment(&node));
+            for child in node.children.iter().rev() {
+                if *child != NIL {
+                    self.stack.push((*child, self.key.len()));
+                }
+            }
+            if node.end {
+                return Some(self.key.clone());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use crate::collections::bit_trie::PatriciaSet;
+    use rand::Rng;
+
+    fn random_tree(count: usize) -> CompactPatriciaTree {
+        let mut rng = rand::rng();
+        let mut tree = CompactPatriciaTree::new();
+        for _ in 0..count {
+            let length = rng.random_range(0..200);
+            let key: Key = (0..length).map(|_| rng.random_bool(0.5)).collect();
+            if rng.random_bool(0.8) {
+                tree.insert(&key);
+            } else {
+                tree.remove(&key);
+            }
+        }
+        tree
+    }
+
+    #[test]
+    fn checksum() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn view_matches_tree() {
+        let tree = random_tree(2000);
+        let bytes = tree.to_bytes();
+        let view = PatriciaView::new(&bytes).unwrap();
+
+        assert_eq!(view.get_length(), tree.get_length());
+        assert!(view.iter().eq(tree.iter()));
+        for key in tree.iter() {
+            assert!(view.search(&key));
+            let mut longer = key.clone();
+            longer.push(true);
+            assert_eq!(view.search(&longer), tree.search(&longer));
+            if !key.is_empty() {
+                let shorter = &key[..key.len() - 1];
+                assert_eq!(view.search(shorter), tree.search(&shorter.to_bitvec()));
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip_drops_garbage() {
+        let mut tree = random_tree(2000);
+        let keys: Vec<Key> = tree.iter().collect();
+        for key in keys.iter().step_by(2) {
+            tree.remove(key);
+        }
+
+        let mut loaded = CompactPatriciaTree::from_bytes(&tree.to_bytes()).unwrap();
+        assert!(loaded.iter().eq(tree.iter()));
+        assert!(loaded.free.is_empty());
+        assert_eq!(loaded.bits.len(), tree.bits.len() - tree.garbage);
+        assert_eq!(loaded.garbage, 0);
+        assert_eq!(loaded.nodes.len(), tree.nodes.len() - tree.free.len());
+
+        // The loaded tree is an ordinary tree again
+        for key in keys.iter().step_by(2) {
+            assert!(loaded.insert(key));
+        }
+        assert_eq!(loaded.get_length(), keys.len());
+
+        let empty = CompactPatriciaTree::new().to_bytes();
+        let view = PatriciaView::new(&empty).unwrap();
+        assert_eq!(view.get_length(), 0);
+        assert!(!view.search(&Key::new()));
+        assert_eq!(view.iter().count(), 0);
+    }
+
+    #[test]
+    fn rejects_damaged_files() {
+        let bytes = random_tree(300).to_bytes();
+
+        let mut flipped = bytes.clone();
+        flipped[HEADER_SIZE + 3] ^= 1;
+        assert_eq!(PatriciaView::new(&flipped).err(), Some(PatriciaFormatError::ChecksumMismatch));
+        assert_eq!(PatriciaView::new(&bytes[..bytes.len() - 1]).err(), Some(PatriciaFormatError::Truncated));
+        assert_eq!(PatriciaView::new(&bytes[..10]).err(), Some(PatriciaFormatError::Truncated));
+
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        assert_eq!(PatriciaView::new(&magic).err(), Some(PatriciaFormatError::BadMagic));
+
+        // Damage that carries a valid checksum is still caught by the structural checks
+        let reseal = |mut bytes: Vec<u8>| {
+            let body = bytes.len() - CHECKSUM_SIZE;
+            let checksum = crc32(&bytes[..body]);
+            bytes[body..].copy_from_slice(&checksum.to_le_bytes());
+            bytes
+        };
+        let mut version = bytes.clone();
+        version[4] = 2;
+        assert_eq!(PatriciaView::new(&reseal(version)).err(), Some(PatriciaFormatError::UnsupportedVersion(2)));
+
+        let mut cycle = bytes.clone();
+        cycle[HEADER_SIZE..HEADER_SIZE + 4].copy_from_slice(&0u32.to_le_bytes());
+        assert_eq!(PatriciaView::new(&reseal(cycle)).err(), Some(PatriciaFormatError::Corrupt));
+
+        let mut count = bytes.clone();
+        count[16] ^= 1;
+        assert_eq!(PatriciaView::new(&reseal(count)).err(), Some(PatriciaFormatError::Corrupt));
+    }
+
+    #[test]
+    fn patricia_set_round_trip() {
+        let tree = random_tree(1000);
+        let set = PatriciaSet::from(&tree);
+        assert!(set.iter().eq(tree.iter()));
+        assert_eq!(set.get_length(), tree.get_length());
+
+        let bytes = set.to_bytes();
+        assert_eq!(bytes, tree.to_bytes());
+        let loaded = PatriciaSet::from_bytes(&bytes).unwrap();
+        assert!(loaded.iter().eq(set.iter()));
+        assert_eq!(loaded.get_length(), set.get_length());
+        assert!(CompactPatriciaTree::from(&loaded).iter().eq(tree.iter()));
+
+        let empty = PatriciaSet::new();
+        assert_eq!(PatriciaSet::from_bytes(&empty.to_bytes()).unwrap().get_length(), 0);
+    }
+
+    #[test]
+    fn rejects_shared_and_keyless_nodes() {
+        let key = |bits: &str| -> Key { bits.chars().map(|c| c == '1').collect() };
+        let mut tree = CompactPatriciaTree::new();
+        for bits in ["00", "01", "1"] {
+            tree.insert(&key(bits));
+        }
+        // Pre-order: the root, 0, 00, 01, then 1 as node 4
+        let bytes = tree.to_bytes();
+        assert!(PatriciaView::new(&bytes).is_ok());
+        let node = |index: usize| HEADER_SIZE + index * NODE_SIZE;
+        let reseal = |mut bytes: Vec<u8>| {
+            let body = bytes.len() - CHECKSUM_SIZE;
+            let checksum = crc32(&bytes[..body]);
+            bytes[body..].copy_from_slice(&checksum.to_le_bytes());
+            bytes
+        };
+
+        // The root's right child aliases 01, which also starts with a 1, and 1 is left unreachable
+        let mut shared = bytes.clone();
+        shared[node(0) + 4..node(0) + 8].copy_from_slice(&3u32.to_le_bytes());
+        assert_eq!(PatriciaView::new(&reseal(shared.clone())).err(), Some(PatriciaFormatError::Corrupt));
+        assert_eq!(CompactPatriciaTree::from_bytes(&reseal(shared)).err(), Some(PatriciaFormatError::Corrupt));
+
+        let mut keyless = bytes.clone();
+        keyless[node(4) + 20] = 0;
+        keyless[16..24].copy_from_slice(&2u64.to_le_bytes());
+        assert_eq!(PatriciaView::new(&reseal(keyless)).err(), Some(PatriciaFormatError::Corrupt));
+    }
+
+    #[test]
+    fn save_and_read_back() {
+        let tree = random_tree(500);
+        let path = std::env::temp_dir().join(format!("patricia_file_test_{}.bin", std::process::id()));
+        tree.save(&path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let view = PatriciaView::new(&bytes).unwrap();
+        assert!(view.iter().eq(tree.iter()));
+    }
+}
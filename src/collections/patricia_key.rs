@@ -0,0 +1,327 @@
+use bitvec::prelude::*;
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ops::{Bound, RangeBounds};
+use crate::collections::loopy_patricia::PatriciaMap;
+
+type Key = BitVec<u8, Msb0>;
+type KeySlice = BitSlice<u8, Msb0>;
+
+// A value that can be spelled as bits, most significant first, so that comparing the bits
+// orders values the same way as the values themselves.
+pub trait BitKey {
+    fn to_bits(&self) -> Key;
+}
+
+// A key type that can be rebuilt from the bits it produced, used when handing keys back.
+pub trait OwnedBitKey: BitKey + Sized {
+    fn from_bits(bits: &KeySlice) -> Self;
+}
+
+impl BitKey for [u8] {
+    fn to_bits(&self) -> Key {
+        self.view_bits::<Msb0>().to_bitvec()
+    }
+}
+
+impl BitKey for Vec<u8> {
+    fn to_bits(&self) -> Key {
+        self.as_slice().to_bits()
+    }
+}
+
+impl OwnedBitKey for Vec<u8> {
+    fn from_bits(bits: &KeySlice) -> Self {
+        bits.to_bitvec().into_vec()
+    }
+}
+
+impl BitKey for str {
+    fn to_bits(&self) -> Key {
+        self.as_bytes().to_bits()
+    }
+}
+
+impl BitKey for String {
+    fn to_bits(&self) -> Key {
+        self.as_bytes().to_bits()
+    }
+}
+
+impl OwnedBitKey for String {
+    fn from_bits(bits: &KeySlice) -> Self {
+        String::from_utf8(Vec::from_bits(bits)).expect("keys are built from valid strings")
+    }
+}
+
+macro_rules! integer_bit_key {
+    ($($integer:ty),*) => {
+        $(
+            impl BitKey for $integer {
+                fn to_bits(&self) -> Key {
+                    self.to_be_bytes().to_bits()
+                }
+            }
+
+            impl OwnedBitKey for $integer {
+                fn from_bits(bits: &KeySlice) -> Self {
+                    bits.load_be::<$integer>()
+                }
+            }
+        )*
+    };
+}
+
+integer_bit_key!(u32, u64, u128);
+
+impl BitKey for Ipv4Addr {
+    fn to_bits(&self) -> Key {
+        u32::from(*self).to_bits()
+    }
+}
+
+impl OwnedBitKey for Ipv4Addr {
+    fn from_bits(bits: &KeySlice) -> Self {
+        Ipv4Addr::from(u32::from_bits(bits))
+    }
+}
+
+impl BitKey for Ipv6Addr {
+    fn to_bits(&self) -> Key {
+        u128::from(*self).to_bits()
+    }
+}
+
+impl OwnedBitKey for Ipv6Addr {
+    fn from_bits(bits: &KeySlice) -> Self {
+        Ipv6Addr::from(u128::from_bits(bits))
+    }
+}
+
+// A leading family bit keeps all IPv4 addresses before all IPv6 ones.
+impl BitKey for IpAddr {
+    fn to_bits(&self) -> Key {
+        let mut bits = Key::new();
+        bits.push(self.is_ipv6());
+        match self {
+            IpAddr::V4(v4) => bits.extend_from_bitslice(&BitKey::to_bits(v4)),
+            IpAddr::V6(v6) => bits.extend_from_bitslice(&BitKey::to_bits(v6)),
+        }
+        bits
+    }
+}
+
+impl OwnedBitKey for IpAddr {
+    fn from_bits(bits: &KeySlice) -> Self {
+        if bits[0] {
+            IpAddr::V6(<Ipv6Addr as OwnedBitKey>::from_bits(&bits[1..]))
+        } else {
+            IpAddr::V4(<Ipv4Addr as OwnedBitKey>::from_bits(&bits[1..]))
+        }
+    }
+}
+
+// A PatriciaMap keyed by ordinary values. Lookups accept borrowed forms, such as `&str` for a
+// map keyed by `String`.
+pub struct KeyedPatriciaMap<K, V> {
+    map: PatriciaMap<V>,
+    marker: PhantomData<K>,
+}
+
+impl<K, V> KeyedPatriciaMap<K, V> where K: OwnedBitKey {
+    pub fn new() -> Self {
+        KeyedPatriciaMap {
+            map: PatriciaMap::new(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.map.get_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.map.insert(&key.to_bits(), value)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V> where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.get(&key.to_bits())
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V> where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.get_mut(&key.to_bits())
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.contains_key(&key.to_bits())
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.remove(&key.to_bits())
+    }
+
+    pub fn longest_prefix_match<Q>(&self, key: &Q) -> Option<(K, &V)> where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.longest_prefix_match(&key.to_bits()).map(|(bits, value)| (K::from_bits(&bits), value))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (K, &V)> + '_ {
+        self.map.iter().map(|(bits, value)| (K::from_bits(&bits), value))
+    }
+
+    pub fn iter_prefix<Q>(&self, prefix: &Q) -> impl Iterator<Item = (K, &V)> + '_ where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.iter_prefix(&prefix.to_bits()).map(|(bits, value)| (K::from_bits(&bits), value))
+    }
+
+    pub fn range<R>(&self, range: R) -> impl Iterator<Item = (K, &V)> + '_ where R: RangeBounds<K> {
+        let bits = |bound: Bound<&K>| bound.map(|key| key.to_bits());
+        self.map
+            .range((bits(range.start_bound()), bits(range.end_bound())))
+            .map(|(bits, value)| (K::from_bits(&bits), value))
+    }
+
+    pub fn first(&self) -> Option<(K, &V)> {
+        self.map.first().map(|(bits, value)| (K::from_bits(&bits), value))
+    }
+
+    pub fn last(&self) -> Option<(K, &V)> {
+        self.map.last().map(|(bits, value)| (K::from_bits(&bits), value))
+    }
+}
+
+pub struct KeyedPatriciaSet<K> {
+    map: KeyedPatriciaMap<K, ()>,
+}
+
+impl<K> KeyedPatriciaSet<K> where K: OwnedBitKey {
+    pub fn new() -> Self {
+        KeyedPatriciaSet {
+            map: KeyedPatriciaMap::new(),
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.map.get_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    // Returns false if the key was already present
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.contains_key(key)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> bool where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.remove(key).is_some()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = K> + '_ {
+        self.map.iter().map(|(key, _)| key)
+    }
+
+    pub fn iter_prefix<Q>(&self, prefix: &Q) -> impl Iterator<Item = K> + '_ where K: Borrow<Q>, Q: BitKey + ?Sized {
+        self.map.iter_prefix(prefix).map(|(key, _)| key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn string_keys() {
+        let mut map = KeyedPatriciaMap::new();
+        for (i, word) in ["romane", "romanus", "romulus", "rubens", "ruber", "rubicon", "rubicundus", "rom"].iter().enumerate() {
+            assert_eq!(map.insert(word.to_string(), i), None);
+        }
+        assert_eq!(map.insert("rom".to_string(), 100), Some(7));
+        assert_eq!(map.get("romulus"), Some(&2));
+        assert_eq!(map.get("roma"), None);
+        assert!(map.contains_key("rom"));
+
+        let words: Vec<String> = map.iter_prefix("rub").map(|(word, _)| word).collect();
+        assert_eq!(words, vec!["rubens", "ruber", "rubicon", "rubicundus"]);
+        assert_eq!(map.first().map(|(word, _)| word), Some("rom".to_string()));
+        assert_eq!(map.last().map(|(word, _)| word), Some("rubicundus".to_string()));
+        assert_eq!(map.longest_prefix_match("romantic"), Some(("rom".to_string(), &100)));
+
+        let words: Vec<String> = map.range("romulus".to_string().."rubicon".to_string()).map(|(word, _)| word).collect();
+        assert_eq!(words, vec!["romulus", "rubens", "ruber"]);
+
+        assert_eq!(map.remove("rom"), Some(100));
+        assert_eq!(map.longest_prefix_match("romantic"), None);
+        assert_eq!(map.get_length(), 7);
+    }
+
+    #[test]
+    fn byte_keys() {
+        let mut set = KeyedPatriciaSet::new();
+        assert!(set.insert(vec![1u8, 2, 3]));
+        assert!(set.insert(vec![1u8]));
+        assert!(!set.insert(vec![1u8]));
+        assert!(set.contains(&[1u8, 2, 3][..]));
+        assert!(!set.contains(&[1u8, 2][..]));
+        assert!(set.remove(&[1u8][..]));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![vec![1u8, 2, 3]]);
+    }
+
+    #[test]
+    fn integer_keys_iterate_in_numeric_order() {
+        let mut rng = rand::rng();
+        let mut map = KeyedPatriciaMap::new();
+        let mut oracle = BTreeMap::new();
+        for _ in 0..1000 {
+            let width = rng.random_range(1..64);
+            let key: u64 = rng.random_range(0..1 << width);
+            let value = rng.random::<u32>();
+            assert_eq!(map.insert(key, value), oracle.insert(key, value));
+        }
+        assert!(map.iter().eq(oracle.iter().map(|(k, v)| (*k, v))));
+        assert!(map.range(1000..1 << 40).eq(oracle.range(1000..1 << 40).map(|(k, v)| (*k, v))));
+
+        let mut small: KeyedPatriciaSet<u32> = KeyedPatriciaSet::new();
+        let mut large: KeyedPatriciaSet<u128> = KeyedPatriciaSet::new();
+        for value in [7u32, 0, u32::MAX, 256] {
+            small.insert(value);
+            large.insert(value as u128 * (u64::MAX as u128));
+        }
+        assert_eq!(small.iter().collect::<Vec<_>>(), vec![0, 7, 256, u32::MAX]);
+        assert_eq!(large.iter().collect::<Vec<_>>(), [0, 7, 256, u32::MAX as u128].map(|v| v * u64::MAX as u128).to_vec());
+    }
+
+    #[test]
+    fn ip_address_keys() {
+        let mut set = KeyedPatriciaSet::new();
+        let addresses: Vec<IpAddr> = ["::1", "10.0.0.2", "192.168.1.1", "10.0.0.1", "2001:db8::1"]
+            .iter()
+            .map(|text| text.parse().unwrap())
+            .collect();
+        for address in &addresses {
+            set.insert(*address);
+        }
+        let ordered: Vec<String> = set.iter().map(|address| address.to_string()).collect();
+        assert_eq!(ordered, vec!["10.0.0.1", "10.0.0.2", "192.168.1.1", "::1", "2001:db8::1"]);
+
+        assert!(set.contains(&"10.0.0.2".parse::<IpAddr>().unwrap()));
+        assert!(!set.contains(&"10.0.0.3".parse::<IpAddr>().unwrap()));
+
+        let mut v6 = KeyedPatriciaMap::new();
+        v6.insert(Ipv6Addr::LOCALHOST, "loopback");
+        v6.insert(Ipv6Addr::UNSPECIFIED, "unspecified");
+        assert_eq!(v6.first(), Some((Ipv6Addr::UNSPECIFIED, &"unspecified")));
+        assert_eq!(v6.get(&Ipv6Addr::LOCALHOST), Some(&"loopback"));
+    }
+}
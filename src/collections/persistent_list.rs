@@ -0,0 +1,243 @@
+use std::rc::Rc;
+use std::sync::Arc;
+use crate::collections::linked_list::{List, QueueStackMix};
+
+// Both flavours share one implementation and differ only in the reference-counted pointer.
+macro_rules! persistent_list {
+    ($list:ident, $node:ident, $iter:ident, $pointer:ident) => {
+        struct $node<T> {
+            value: T,
+            next: Option<$pointer<$node<T>>>,
+        }
+
+        pub struct $list<T> {
+            head: Option<$pointer<$node<T>>>,
+            length: usize,
+        }
+
+        impl<T> $list<T> {
+            pub fn new() -> Self {
+                $list {
+                    head: None,
+                    length: 0,
+                }
+            }
+
+            pub fn get_length(&self) -> usize {
+                self.length
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.length == 0
+            }
+
+            // Returns a new list with `value` in front; the current list becomes its shared tail.
+            pub fn push_front(&self, value: T) -> Self {
+                $list {
+                    head: Some($pointer::new($node {
+                        value,
+                        next: self.head.clone(),
+                    })),
+                    length: self.length + 1,
+                }
+            }
+
+            pub fn head(&self) -> Option<&T> {
+                self.head.as_ref().map(|n| &n.value)
+            }
+
+            // The tail of an empty list is the empty list.
+            pub fn tail(&self) -> Self {
+                match &self.head {
+                    None => $list::new(),
+                    Some(n) => $list {
+                        head: n.next.clone(),
+                        length: self.length - 1,
+                    },
+                }
+            }
+
+            pub fn iter(&self) -> $iter<'_, T> {
+                $iter { next: self.head.as_deref() }
+            }
+        }
+
+        impl<T> Clone for $list<T> {
+            fn clone(&self) -> Self {
+                $list {
+                    head: self.head.clone(),
+                    length: self.length,
+                }
+            }
+        }
+
+        // Unlinks iteratively and stops at the first node another list still shares. Unlike
+        // try_unwrap, into_inner hands a node to exactly one of several lists dropping it at once
+        // on different threads, so none of them is left to free the rest recursively.
+        impl<T> Drop for $list<T> {
+            fn drop(&mut self) {
+                let mut iter = self.head.take();
+
+                while let Some(mut node) = iter.and_then($pointer::into_inner) {
+                    iter = node.next.take();
+                }
+            }
+        }
+
+        pub struct $iter<'a, T> {
+            next: Option<&'a $node<T>>,
+        }
+
+        impl<'a, T> Iterator for $iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.next.take().map(|node| {
+                    self.next = node.next.as_deref();
+                    &node.value
+                })
+            }
+        }
+
+        impl<T> From<List<T>> for $list<T> {
+            fn from(mut list: List<T>) -> Self {
+                list.reverse();
+                let mut result = $list::new();
+                for value in list {
+                    result = result.push_front(value);
+                }
+                result
+            }
+        }
+
+        impl<T> From<&$list<T>> for List<T> where T: Clone {
+            fn from(persistent: &$list<T>) -> Self {
+                let mut list = List::new();
+                for value in persistent.iter() {
+                    list.push_to_beginning(value.clone()).unwrap();
+                }
+                list.reverse();
+                list
+            }
+        }
+    };
+}
+
+persistent_list!(PersistentList, PersistentNode, Iter, Rc);
+persistent_list!(ArcPersistentList, ArcPersistentNode, ArcIter, Arc);
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use std::sync::Barrier;
+    use std::thread;
+
+    #[test]
+    fn push_head_tail() {
+        let empty: PersistentList<u32> = PersistentList::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.head(), None);
+        assert_eq!(empty.tail().get_length(), 0);
+
+        let one = empty.push_front(1);
+        let two = one.push_front(2);
+        let three = two.push_front(3);
+
+        assert_eq!(three.get_length(), 3);
+        assert_eq!(three.head(), Some(&3));
+        assert_eq!(three.tail().head(), Some(&2));
+        assert_eq!(three.iter().cloned().collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        // Older versions are untouched by later pushes.
+        assert_eq!(one.iter().cloned().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(two.iter().cloned().collect::<Vec<_>>(), vec![2, 1]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn structural_sharing() {
+        let base = PersistentList::new().push_front(1).push_front(2);
+        let left = base.push_front(3);
+        let right = base.push_front(4);
+
+        assert!(Rc::ptr_eq(left.tail().head.as_ref().unwrap(), base.head.as_ref().unwrap()));
+        assert!(Rc::ptr_eq(right.tail().head.as_ref().unwrap(), base.head.as_ref().unwrap()));
+        assert_eq!(Rc::strong_count(base.head.as_ref().unwrap()), 3);
+
+        drop(left);
+        assert_eq!(Rc::strong_count(base.head.as_ref().unwrap()), 2);
+        assert_eq!(right.iter().cloned().collect::<Vec<_>>(), vec![4, 2, 1]);
+    }
+
+    #[test]
+    fn long_list_drop() {
+        let mut list = PersistentList::new();
+        for i in 0..1_000_000 {
+            list = list.push_front(i);
+        }
+        let shared = list.tail();
+        drop(list);
+        assert_eq!(shared.head(), Some(&999_998));
+        drop(shared);
+    }
+
+    #[test]
+    fn list_conversion() {
+        let mut list = List::new();
+        for i in 1..5 {
+            assert!(list.push_to_end(i).is_ok());
+        }
+
+        let persistent = PersistentList::from(list);
+        assert_eq!(persistent.get_length(), 4);
+        assert_eq!(persistent.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        let back = List::from(&persistent.push_front(0));
+        assert_eq!(back.get_length(), 5);
+        assert_eq!(back.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let base = ArcPersistentList::new().push_front(1).push_front(2);
+
+        let handles: Vec<_> = (0..4).map(|i| {
+            let base = base.clone();
+            thread::spawn(move || {
+                let extended = base.push_front(10 + i);
+                extended.iter().sum::<u32>()
+            })
+        }).collect();
+
+        let sums: Vec<u32> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(sums, vec![13, 14, 15, 16]);
+        assert_eq!(base.iter().cloned().collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+
+    #[test]
+    fn concurrent_drops_of_long_list() {
+        // Every clone is dropped at once, so several threads race to release the head node
+        for _ in 0..50 {
+            let mut list = ArcPersistentList::new();
+            for i in 0..100_000 {
+                list = list.push_front(i);
+            }
+            let barrier = Arc::new(Barrier::new(16));
+            let handles: Vec<_> = (0..16).map(|_| {
+                let list = list.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    drop(list);
+                })
+            }).collect();
+            drop(list);
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+        }
+    }
+}
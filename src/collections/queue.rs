@@ -0,0 +1,97 @@
+use crate::collections::deque::Deque;
+use crate::collections::linked_list::QueueStackMix;
+
+// FIFO queue: values enter at the end of the deque and leave from its beginning.
+pub struct Queue<T> {
+    deque: Deque<T>,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Queue {
+            deque: Deque::new(),
+        }
+    }
+
+    pub fn with_capacity_limit(limit: usize) -> Self {
+        Queue {
+            deque: Deque::with_capacity_limit(limit),
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.deque.get_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.deque.is_full()
+    }
+
+    // Hands the value back when the queue is full.
+    pub fn enqueue(&mut self, value: T) -> Result<(), T> {
+        if self.deque.is_full() {
+            return Err(value);
+        }
+        self.deque.push_to_end(value).unwrap();
+        Ok(())
+    }
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.deque.pop_from_beginning()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.deque.peek_beginning()
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.deque.peek_mut_beginning()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn enqueue_dequeue() {
+        let mut q: Queue<u32> = Queue::new();
+        assert!(q.is_empty());
+        assert_eq!(q.dequeue(), None);
+        assert_eq!(q.front(), None);
+
+        assert_eq!(q.enqueue(1), Ok(()));
+        assert_eq!(q.enqueue(2), Ok(()));
+        assert_eq!(q.enqueue(3), Ok(()));
+        assert_eq!(q.get_length(), 3);
+        assert_eq!(q.front(), Some(&1));
+
+        if let Some(v) = q.front_mut() {
+            *v = 4;
+        }
+        assert_eq!(q.dequeue(), Some(4));
+        assert_eq!(q.dequeue(), Some(2));
+        assert_eq!(q.enqueue(5), Ok(()));
+        assert_eq!(q.dequeue(), Some(3));
+        assert_eq!(q.dequeue(), Some(5));
+        assert_eq!(q.dequeue(), None);
+    }
+
+    #[test]
+    fn capacity_limit() {
+        let mut q: Queue<u32> = Queue::with_capacity_limit(2);
+        assert_eq!(q.enqueue(1), Ok(()));
+        assert_eq!(q.enqueue(2), Ok(()));
+        assert!(q.is_full());
+        assert_eq!(q.enqueue(3), Err(3));
+        assert_eq!(q.dequeue(), Some(1));
+        assert_eq!(q.enqueue(3), Ok(()));
+        assert_eq!(q.dequeue(), Some(2));
+        assert_eq!(q.dequeue(), Some(3));
+    }
+}
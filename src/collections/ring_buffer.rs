@@ -0,0 +1,371 @@
+use std::mem::MaybeUninit;
+use std::ops::{Index, IndexMut};
+use std::slice;
+use crate::collections::linked_list::QueueStackMix;
+
+// Contiguous double-ended queue. Slots `head..head + length` (wrapping around the end of
+// `buffer`) are initialised; every other slot is uninitialised.
+pub struct RingBuffer<T> {
+    buffer: Vec<MaybeUninit<T>>,
+    head: usize,
+    length: usize,
+    overwrite: bool,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new() -> Self {
+        RingBuffer {
+            buffer: Vec::new(),
+            head: 0,
+            length: 0,
+            overwrite: false,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut ring = RingBuffer::new();
+        ring.buffer.resize_with(capacity, MaybeUninit::uninit);
+        ring
+    }
+
+    // Fixed-size sliding window: pushing onto a full buffer overwrites the value at the opposite end.
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity > 0, "a bounded ring buffer needs room for at least one value");
+        let mut ring = RingBuffer::with_capacity(capacity);
+        ring.overwrite = true;
+        ring
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.length == self.buffer.len()
+    }
+
+    fn physical_index(&self, index: usize) -> usize {
+        let i = self.head + index;
+        if i >= self.buffer.len() { i - self.buffer.len() } else { i }
+    }
+
+    fn grow(&mut self) {
+        let capacity = (self.buffer.len() * 2).max(4);
+        let mut buffer = Vec::with_capacity(capacity);
+        for i in 0..self.length {
+            let index = self.physical_index(i);
+            // The old slot is never read again; the old Vec drops without touching its contents.
+            buffer.push(MaybeUninit::new(unsafe { self.buffer[index].assume_init_read() }));
+        }
+        buffer.resize_with(capacity, MaybeUninit::uninit);
+
+        self.buffer = buffer;
+        self.head = 0;
+    }
+
+    // Pushes to the end, returning the value pushed out of the beginning when a bounded buffer is full.
+    pub fn push_back(&mut self, value: T) -> Option<T> {
+        let mut evicted = None;
+        if self.is_full() {
+            if self.overwrite {
+                evicted = self.pop_front();
+            } else {
+                self.grow();
+            }
+        }
+
+        let index = self.physical_index(self.length);
+        self.buffer[index].write(value);
+        self.length += 1;
+        evicted
+    }
+
+    // Pushes to the beginning, returning the value pushed out of the end when a bounded buffer is full.
+    pub fn push_front(&mut self, value: T) -> Option<T> {
+        let mut evicted = None;
+        if self.is_full() {
+            if self.overwrite {
+                evicted = self.pop_back();
+            } else {
+                self.grow();
+            }
+        }
+
+        self.head = self.physical_index(self.buffer.len() - 1);
+        self.buffer[self.head].write(value);
+        self.length += 1;
+        evicted
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+        let index = self.head;
+        self.head = self.physical_index(1);
+        self.length -= 1;
+        Some(unsafe { self.buffer[index].assume_init_read() })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+        self.length -= 1;
+        let index = self.physical_index(self.length);
+        Some(unsafe { self.buffer[index].assume_init_read() })
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.length {
+            return None;
+        }
+        Some(unsafe { self.buffer[self.physical_index(index)].assume_init_ref() })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.length {
+            return None;
+        }
+        let index = self.physical_index(index);
+        Some(unsafe { self.buffer[index].assume_init_mut() })
+    }
+
+    // The contents in order, split where they wrap around the end of the buffer.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let first_length = self.length.min(self.buffer.len() - self.head);
+        let first = &self.buffer[self.head..self.head + first_length];
+        let second = &self.buffer[..self.length - first_length];
+        unsafe { (Self::assume_init_slice(first), Self::assume_init_slice(second)) }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let first_length = self.length.min(self.buffer.len() - self.head);
+        let second_length = self.length - first_length;
+        let (wrapped, rest) = self.buffer.split_at_mut(self.head);
+        let first = &mut rest[..first_length];
+        let second = &mut wrapped[..second_length];
+        unsafe { (Self::assume_init_slice_mut(first), Self::assume_init_slice_mut(second)) }
+    }
+
+    // Moves the contents so they start at the beginning of the buffer and returns them as one slice.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        self.buffer.rotate_left(self.head);
+        self.head = 0;
+        unsafe { Self::assume_init_slice_mut(&mut self.buffer[..self.length]) }
+    }
+
+    // Rotates so the value at index `count` becomes the first one.
+    pub fn rotate_left(&mut self, count: usize) {
+        assert!(count <= self.length, "cannot rotate by more than the length");
+        if count > self.length / 2 {
+            return self.rotate_right(self.length - count);
+        }
+        for _ in 0..count {
+            let value = self.pop_front().unwrap();
+            let index = self.physical_index(self.length);
+            self.buffer[index].write(value);
+            self.length += 1;
+        }
+    }
+
+    // Rotates so the value at index `length - count` becomes the first one.
+    pub fn rotate_right(&mut self, count: usize) {
+        assert!(count <= self.length, "cannot rotate by more than the length");
+        if count > self.length / 2 {
+            return self.rotate_left(self.length - count);
+        }
+        for _ in 0..count {
+            let value = self.pop_back().unwrap();
+            self.head = self.physical_index(self.buffer.len() - 1);
+            self.buffer[self.head].write(value);
+            self.length += 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+        self.head = 0;
+    }
+
+    pub fn iter(&self) -> std::iter::Chain<slice::Iter<'_, T>, slice::Iter<'_, T>> {
+        let (first, second) = self.as_slices();
+        first.iter().chain(second.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> std::iter::Chain<slice::IterMut<'_, T>, slice::IterMut<'_, T>> {
+        let (first, second) = self.as_mut_slices();
+        first.iter_mut().chain(second.iter_mut())
+    }
+
+    // Callers must only pass slots that are initialised.
+    unsafe fn assume_init_slice(slots: &[MaybeUninit<T>]) -> &[T] {
+        unsafe { &*(slots as *const [MaybeUninit<T>] as *const [T]) }
+    }
+
+    unsafe fn assume_init_slice_mut(slots: &mut [MaybeUninit<T>]) -> &mut [T] {
+        unsafe { &mut *(slots as *mut [MaybeUninit<T>] as *mut [T]) }
+    }
+}
+
+impl<T> Index<usize> for RingBuffer<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for RingBuffer<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T> Drop for RingBuffer<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+// In bounded mode pushes never fail; they overwrite the value at the other end instead.
+impl<T> QueueStackMix<T> for RingBuffer<T> {
+    fn push_to_beginning(&mut self, value: T) -> Result<(), ()> {
+        self.push_front(value);
+        Ok(())
+    }
+    fn pop_from_beginning(&mut self) -> Option<T> {
+        self.pop_front()
+    }
+    fn push_to_end(&mut self, value: T) -> Result<(), ()> {
+        self.push_back(value);
+        Ok(())
+    }
+    fn pop_from_end(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+    fn peek_beginning(&self) -> Option<&T> {
+        self.get(0)
+    }
+    fn peek_last(&self) -> Option<&T> {
+        self.get(self.length.checked_sub(1)?)
+    }
+    fn peek_mut_beginning(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+    fn peek_mut_last(&mut self) -> Option<&mut T> {
+        self.get_mut(self.length.checked_sub(1)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+
+    #[test]
+    fn against_vec_deque() {
+        let mut rng = rand::rng();
+        let mut ring = RingBuffer::new();
+        let mut oracle = VecDeque::new();
+
+        for i in 0..5000 {
+            match rng.random_range(0..6) {
+                0 | 1 => {
+                    ring.push_back(i);
+                    oracle.push_back(i);
+                }
+                2 => {
+                    ring.push_front(i);
+                    oracle.push_front(i);
+                }
+                3 => assert_eq!(ring.pop_front(), oracle.pop_front()),
+                4 => assert_eq!(ring.pop_back(), oracle.pop_back()),
+                _ => {
+                    let count = rng.random_range(0..=oracle.len());
+                    ring.rotate_left(count);
+                    oracle.rotate_left(count);
+                }
+            }
+            assert_eq!(ring.get_length(), oracle.len());
+            assert_eq!(ring.peek_beginning(), oracle.front());
+            assert_eq!(ring.peek_last(), oracle.back());
+        }
+
+        assert!(ring.iter().eq(oracle.iter()));
+        let (first, second) = ring.as_slices();
+        assert_eq!([first, second].concat(), oracle.iter().cloned().collect::<Vec<_>>());
+        for i in 0..oracle.len() {
+            assert_eq!(ring[i], oracle[i]);
+        }
+        assert_eq!(ring.get(oracle.len()), None);
+    }
+
+    #[test]
+    fn slices_and_contiguous() {
+        let mut ring = RingBuffer::with_capacity(4);
+        ring.push_back(3);
+        ring.push_back(4);
+        ring.push_front(2);
+        ring.push_front(1);
+        assert_eq!(ring.capacity(), 4);
+
+        let (first, second) = ring.as_slices();
+        assert_eq!(first, &[1, 2]);
+        assert_eq!(second, &[3, 4]);
+
+        ring.make_contiguous().reverse();
+        assert_eq!(ring.as_slices(), (&[4, 3, 2, 1][..], &[][..]));
+
+        ring[1] = 30;
+        for v in ring.iter_mut() {
+            *v += 1;
+        }
+        assert_eq!(ring.iter().cloned().collect::<Vec<_>>(), vec![5, 31, 3, 2]);
+
+        ring.rotate_right(1);
+        assert_eq!(ring.iter().cloned().collect::<Vec<_>>(), vec![2, 5, 31, 3]);
+    }
+
+    #[test]
+    fn bounded_window() {
+        let mut window = RingBuffer::bounded(3);
+        assert_eq!(window.push_back(1), None);
+        assert_eq!(window.push_back(2), None);
+        assert_eq!(window.push_back(3), None);
+        assert_eq!(window.push_back(4), Some(1));
+        assert_eq!(window.push_back(5), Some(2));
+        assert_eq!(window.iter().sum::<i32>(), 12);
+        assert_eq!(window.capacity(), 3);
+
+        assert_eq!(window.push_front(0), Some(5));
+        assert_eq!(window.iter().cloned().collect::<Vec<_>>(), vec![0, 3, 4]);
+        assert!(window.push_to_end(6).is_ok());
+        assert_eq!(window.iter().cloned().collect::<Vec<_>>(), vec![3, 4, 6]);
+    }
+
+    #[test]
+    fn drops_remaining_values() {
+        let counter = Rc::new(());
+        let mut ring = RingBuffer::new();
+        for _ in 0..10 {
+            ring.push_front(counter.clone());
+            ring.push_back(counter.clone());
+        }
+        drop(ring.pop_back());
+        assert_eq!(Rc::strong_count(&counter), 20);
+        drop(ring);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
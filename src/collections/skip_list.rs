@@ -0,0 +1,425 @@
+use std::ops::{Bound, RangeBounds};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const MAX_LEVEL: usize = 32;
+const NIL: usize = usize::MAX;
+const HEAD: usize = 0;
+
+// `span[i]` counts how many positions `forward[i]` jumps over, which makes rank queries O(log n).
+struct SkipNode<K, V> {
+    entry: Option<(K, V)>,
+    forward: Vec<usize>,
+    span: Vec<usize>,
+}
+
+// Nodes live in `nodes` and link to each other by index; slot 0 is the head sentinel and removed
+// slots are recycled through `free`.
+pub struct SkipListMap<K, V> {
+    nodes: Vec<SkipNode<K, V>>,
+    free: Vec<usize>,
+    level: usize,
+    length: usize,
+    rng: StdRng,
+}
+
+impl<K, V> SkipListMap<K, V> where K: Ord {
+    pub fn new() -> Self {
+        Self::with_rng(StdRng::from_os_rng())
+    }
+
+    // The same seed always builds the same tower heights, which keeps tests reproducible.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Self {
+        SkipListMap {
+            nodes: vec![SkipNode {
+                entry: None,
+                forward: vec![NIL; MAX_LEVEL],
+                span: vec![0; MAX_LEVEL],
+            }],
+            free: Vec::new(),
+            level: 1,
+            length: 0,
+            rng,
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    fn key(&self, node: usize) -> &K {
+        &self.nodes[node].entry.as_ref().unwrap().0
+    }
+
+    fn random_level(&mut self) -> usize {
+        let mut level = 1;
+        while level < MAX_LEVEL && self.rng.random_bool(0.5) {
+            level += 1;
+        }
+        level
+    }
+
+    // Last node on each level whose key is smaller than `key`, with its rank (head is rank 0).
+    fn predecessors(&self, key: &K) -> ([usize; MAX_LEVEL], [usize; MAX_LEVEL]) {
+        let mut update = [HEAD; MAX_LEVEL];
+        let mut rank = [0; MAX_LEVEL];
+        let mut x = HEAD;
+
+        for i in (0..self.level).rev() {
+            rank[i] = if i + 1 == self.level { 0 } else { rank[i + 1] };
+            loop {
+                let next = self.nodes[x].forward[i];
+                if next == NIL || self.key(next) >= key {
+                    break;
+                }
+                rank[i] += self.nodes[x].span[i];
+                x = next;
+            }
+            update[i] = x;
+        }
+        (update, rank)
+    }
+
+    fn find(&self, key: &K) -> Option<usize> {
+        let (update, _) = self.predecessors(key);
+        let next = self.nodes[update[0]].forward[0];
+        if next != NIL && self.key(next) == key { Some(next) } else { None }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(|node| &self.nodes[node].entry.as_ref().unwrap().1)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let node = self.find(key)?;
+        Some(&mut self.nodes[node].entry.as_mut().unwrap().1)
+    }
+
+    // Returns the previous value when the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (mut update, mut rank) = self.predecessors(&key);
+
+        let next = self.nodes[update[0]].forward[0];
+        if next != NIL && *self.key(next) == key {
+            let entry = self.nodes[next].entry.as_mut().unwrap();
+            return Some(std::mem::replace(&mut entry.1, value));
+        }
+
+        let level = self.random_level();
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEAD;
+                self.nodes[HEAD].span[i] = self.length;
+            }
+            self.level = level;
+        }
+
+        let node = SkipNode {
+            entry: Some((key, value)),
+            forward: vec![NIL; level],
+            span: vec![0; level],
+        };
+        let new = match self.free.pop() {
+            Some(slot) => {
+                self.nodes[slot] = node;
+                slot
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        };
+
+        for (i, &previous) in update.iter().enumerate().take(level) {
+            self.nodes[new].forward[i] = self.nodes[previous].forward[i];
+            self.nodes[previous].forward[i] = new;
+            self.nodes[new].span[i] = self.nodes[previous].span[i] - (rank[0] - rank[i]);
+            self.nodes[previous].span[i] = rank[0] - rank[i] + 1;
+        }
+        for (i, &previous) in update.iter().enumerate().take(self.level).skip(level) {
+            self.nodes[previous].span[i] += 1;
+        }
+
+        self.length += 1;
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (update, _) = self.predecessors(key);
+        let target = self.nodes[update[0]].forward[0];
+        if target == NIL || self.key(target) != key {
+            return None;
+        }
+
+        for (i, &previous) in update.iter().enumerate().take(self.level) {
+            if self.nodes[previous].forward[i] == target {
+                self.nodes[previous].span[i] += self.nodes[target].span[i];
+                self.nodes[previous].span[i] -= 1;
+                self.nodes[previous].forward[i] = self.nodes[target].forward[i];
+            } else {
+                self.nodes[previous].span[i] -= 1;
+            }
+        }
+        while self.level > 1 && self.nodes[HEAD].forward[self.level - 1] == NIL {
+            self.level -= 1;
+        }
+
+        self.length -= 1;
+        self.free.push(target);
+        let node = &mut self.nodes[target];
+        node.forward = Vec::new();
+        node.span = Vec::new();
+        node.entry.take().map(|(_, value)| value)
+    }
+
+    // Zero-based position of `key` in sorted order.
+    pub fn rank(&self, key: &K) -> Option<usize> {
+        let (update, rank) = self.predecessors(key);
+        let next = self.nodes[update[0]].forward[0];
+        if next != NIL && self.key(next) == key { Some(rank[0]) } else { None }
+    }
+
+    // Entry at zero-based position `index` in sorted order.
+    pub fn get_by_rank(&self, index: usize) -> Option<(&K, &V)> {
+        if index >= self.length {
+            return None;
+        }
+
+        let target = index + 1;
+        let mut traversed = 0;
+        let mut x = HEAD;
+        for i in (0..self.level).rev() {
+            while self.nodes[x].forward[i] != NIL && traversed + self.nodes[x].span[i] <= target {
+                traversed += self.nodes[x].span[i];
+                x = self.nodes[x].forward[i];
+            }
+            if traversed == target {
+                break;
+            }
+        }
+
+        self.nodes[x].entry.as_ref().map(|(k, v)| (k, v))
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.get_by_rank(0)
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.get_by_rank(self.length.checked_sub(1)?)
+    }
+
+    pub fn iter(&self) -> Range<'_, K, V, std::ops::RangeFull> {
+        self.range(..)
+    }
+
+    pub fn range<R>(&self, range: R) -> Range<'_, K, V, R> where R: RangeBounds<K> {
+        let next = match range.start_bound() {
+            Bound::Unbounded => self.nodes[HEAD].forward[0],
+            Bound::Included(start) => self.nodes[self.predecessors(start).0[0]].forward[0],
+            Bound::Excluded(start) => {
+                let next = self.nodes[self.predecessors(start).0[0]].forward[0];
+                if next != NIL && self.key(next) == start { self.nodes[next].forward[0] } else { next }
+            }
+        };
+        Range { map: self, next, range }
+    }
+}
+
+pub struct Range<'a, K, V, R> {
+    map: &'a SkipListMap<K, V>,
+    next: usize,
+    range: R,
+}
+
+impl<'a, K, V, R> Iterator for Range<'a, K, V, R> where K: Ord, R: RangeBounds<K> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == NIL {
+            return None;
+        }
+
+        let (key, value) = self.map.nodes[self.next].entry.as_ref().unwrap();
+        let in_range = match self.range.end_bound() {
+            Bound::Unbounded => true,
+            Bound::Included(end) => key <= end,
+            Bound::Excluded(end) => key < end,
+        };
+        if !in_range {
+            self.next = NIL;
+            return None;
+        }
+
+        self.next = self.map.nodes[self.next].forward[0];
+        Some((key, value))
+    }
+}
+
+pub struct SkipListSet<K> {
+    map: SkipListMap<K, ()>,
+}
+
+impl<K> SkipListSet<K> where K: Ord {
+    pub fn new() -> Self {
+        SkipListSet { map: SkipListMap::new() }
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        SkipListSet { map: SkipListMap::with_seed(seed) }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.map.get_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    // Returns false when the key was already present.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn rank(&self, key: &K) -> Option<usize> {
+        self.map.rank(key)
+    }
+
+    pub fn get_by_rank(&self, index: usize) -> Option<&K> {
+        self.map.get_by_rank(index).map(|(k, _)| k)
+    }
+
+    pub fn first(&self) -> Option<&K> {
+        self.map.first().map(|(k, _)| k)
+    }
+
+    pub fn last(&self) -> Option<&K> {
+        self.map.last().map(|(k, _)| k)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.map.iter().map(|(k, _)| k)
+    }
+
+    pub fn range<R>(&self, range: R) -> impl Iterator<Item = &K> where R: RangeBounds<K> {
+        self.map.range(range).map(|(k, _)| k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn against_btree_map() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut map = SkipListMap::with_seed(42);
+        let mut oracle = BTreeMap::new();
+
+        for i in 0..5000 {
+            let key = rng.random_range(0..500);
+            if rng.random_bool(0.6) {
+                assert_eq!(map.insert(key, i), oracle.insert(key, i));
+            } else {
+                assert_eq!(map.remove(&key), oracle.remove(&key));
+            }
+            assert_eq!(map.get_length(), oracle.len());
+        }
+
+        assert!(map.iter().eq(oracle.iter()));
+        for (index, (key, value)) in oracle.iter().enumerate() {
+            assert_eq!(map.get(key), Some(value));
+            assert_eq!(map.rank(key), Some(index));
+            assert_eq!(map.get_by_rank(index), Some((key, value)));
+        }
+        assert_eq!(map.get_by_rank(oracle.len()), None);
+        assert_eq!(map.first(), oracle.iter().next());
+        assert_eq!(map.last(), oracle.iter().next_back());
+        for key in 0..500 {
+            assert_eq!(map.contains_key(&key), oracle.contains_key(&key));
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let mut map = SkipListMap::with_seed(1);
+        for key in (0..100).step_by(10) {
+            map.insert(key, key * 2);
+        }
+
+        fn keys<R: RangeBounds<i32>>(iter: Range<'_, i32, i32, R>) -> Vec<i32> {
+            iter.map(|(k, _)| *k).collect()
+        }
+        assert_eq!(keys(map.range(20..50)), vec![20, 30, 40]);
+        assert_eq!(keys(map.range(15..=50)), vec![20, 30, 40, 50]);
+        assert_eq!(keys(map.range(85..)), vec![90]);
+        assert_eq!(keys(map.range(..10)), vec![0]);
+        assert_eq!(keys(map.range((Bound::Excluded(30), Bound::Excluded(60)))), vec![40, 50]);
+        assert_eq!(keys(map.range(200..)), Vec::<i32>::new());
+
+        if let Some(v) = map.get_mut(&40) {
+            *v = 0;
+        }
+        assert_eq!(map.get(&40), Some(&0));
+        assert_eq!(map.rank(&45), None);
+    }
+
+    #[test]
+    fn seeded_lists_are_identical() {
+        let mut a = SkipListMap::with_seed(3);
+        let mut b = SkipListMap::with_seed(3);
+        for key in 0..1000 {
+            a.insert(key, ());
+            b.insert(key, ());
+        }
+        assert_eq!(a.level, b.level);
+        assert!(a.nodes.iter().zip(b.nodes.iter()).all(|(x, y)| x.forward == y.forward));
+    }
+
+    #[test]
+    fn set() {
+        let mut set = SkipListSet::with_seed(9);
+        assert!(set.is_empty());
+        assert!(set.insert(5));
+        assert!(set.insert(1));
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert_eq!(set.get_length(), 3);
+
+        assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![1, 3, 5]);
+        assert_eq!(set.range(2..).cloned().collect::<Vec<_>>(), vec![3, 5]);
+        assert_eq!(set.rank(&5), Some(2));
+        assert_eq!(set.get_by_rank(1), Some(&3));
+        assert_eq!(set.first(), Some(&1));
+        assert_eq!(set.last(), Some(&5));
+
+        assert!(set.remove(&3));
+        assert!(!set.remove(&3));
+        assert!(!set.contains(&3));
+        assert_eq!(set.rank(&5), Some(1));
+    }
+}
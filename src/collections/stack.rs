@@ -0,0 +1,97 @@
+use crate::collections::linked_list::{List, QueueStackMix};
+
+// LIFO stack on top of the singly linked list, whose front is O(1) for every operation.
+pub struct Stack<T> {
+    list: List<T>,
+    limit: Option<usize>,
+}
+
+impl<T> Stack<T> {
+    pub fn new() -> Self {
+        Stack {
+            list: List::new(),
+            limit: None,
+        }
+    }
+
+    pub fn with_capacity_limit(limit: usize) -> Self {
+        Stack {
+            list: List::new(),
+            limit: Some(limit),
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.list.get_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.get_length() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.limit.is_some_and(|limit| self.list.get_length() >= limit)
+    }
+
+    // Hands the value back when the stack is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.list.push_to_beginning(value).unwrap();
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.list.pop_from_beginning()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.list.peek_beginning()
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.list.peek_mut_beginning()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn push_pop() {
+        let mut s: Stack<u32> = Stack::new();
+        assert!(s.is_empty());
+        assert_eq!(s.pop(), None);
+        assert_eq!(s.peek(), None);
+
+        assert_eq!(s.push(1), Ok(()));
+        assert_eq!(s.push(2), Ok(()));
+        assert_eq!(s.push(3), Ok(()));
+        assert_eq!(s.get_length(), 3);
+        assert_eq!(s.peek(), Some(&3));
+
+        if let Some(v) = s.peek_mut() {
+            *v = 4;
+        }
+        assert_eq!(s.pop(), Some(4));
+        assert_eq!(s.pop(), Some(2));
+        assert_eq!(s.pop(), Some(1));
+        assert_eq!(s.pop(), None);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn capacity_limit() {
+        let mut s: Stack<u32> = Stack::with_capacity_limit(2);
+        assert_eq!(s.push(1), Ok(()));
+        assert_eq!(s.push(2), Ok(()));
+        assert!(s.is_full());
+        assert_eq!(s.push(3), Err(3));
+        assert_eq!(s.pop(), Some(2));
+        assert_eq!(s.push(3), Ok(()));
+        assert_eq!(s.peek(), Some(&3));
+    }
+}
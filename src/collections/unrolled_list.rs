@@ -0,0 +1,365 @@
+use std::slice;
+use std::vec;
+
+struct UnrolledNode<T> {
+    values: Vec<T>,
+    next: Option<Box<UnrolledNode<T>>>,
+}
+
+impl<T> UnrolledNode<T> {
+    fn new(values: Vec<T>, next: Option<Box<UnrolledNode<T>>>) -> Box<Self> {
+        Box::new(UnrolledNode { values, next })
+    }
+}
+
+// Singly linked list whose nodes each hold up to `N` values next to each other. Nodes split when
+// they overflow and merge with their neighbour once they drop below half full.
+pub struct UnrolledList<T, const N: usize = 16> {
+    head: Option<Box<UnrolledNode<T>>>,
+    length: usize,
+}
+
+impl<T, const N: usize> UnrolledList<T, N> {
+    pub fn new() -> Self {
+        assert!(N >= 2, "nodes must hold at least two values to be split");
+        UnrolledList {
+            head: None,
+            length: 0,
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let mut node = self.head.as_deref();
+        let mut offset = index;
+        while let Some(n) = node {
+            if offset < n.values.len() {
+                return n.values.get(offset);
+            }
+            offset -= n.values.len();
+            node = n.next.as_deref();
+        }
+        None
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let mut node = self.head.as_deref_mut();
+        let mut offset = index;
+        while let Some(n) = node {
+            if offset < n.values.len() {
+                return n.values.get_mut(offset);
+            }
+            offset -= n.values.len();
+            node = n.next.as_deref_mut();
+        }
+        None
+    }
+
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), ()> {
+        if index > self.length {
+            return Err(());
+        }
+        self.length += 1;
+
+        let mut node = match self.head.as_mut() {
+            Some(node) => node,
+            None => {
+                let mut values = Vec::with_capacity(N);
+                values.push(value);
+                self.head = Some(UnrolledNode::new(values, None));
+                return Ok(());
+            }
+        };
+
+        let mut offset = index;
+        while offset > node.values.len() {
+            offset -= node.values.len();
+            node = node.next.as_mut().unwrap();
+        }
+
+        if node.values.len() < N {
+            node.values.insert(offset, value);
+            return Ok(());
+        }
+
+        let mut second_half = Vec::with_capacity(N);
+        second_half.extend(node.values.drain(N / 2..));
+        let mut new_node = UnrolledNode::new(second_half, node.next.take());
+        if offset <= node.values.len() {
+            node.values.insert(offset, value);
+        } else {
+            new_node.values.insert(offset - node.values.len(), value);
+        }
+        node.next = Some(new_node);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.length {
+            return None;
+        }
+        self.length -= 1;
+
+        let mut link = &mut self.head;
+        let mut offset = index;
+        while offset >= link.as_ref().unwrap().values.len() {
+            offset -= link.as_ref().unwrap().values.len();
+            link = &mut link.as_mut().unwrap().next;
+        }
+
+        let node = link.as_mut().unwrap();
+        let value = node.values.remove(offset);
+
+        if node.values.is_empty() {
+            *link = node.next.take();
+        } else if node.values.len() < N / 2 {
+            if let Some(next) = node.next.as_mut() {
+                if node.values.len() + next.values.len() <= N {
+                    node.values.append(&mut next.values);
+                    node.next = next.next.take();
+                } else {
+                    node.values.push(next.values.remove(0));
+                }
+            }
+        }
+        Some(value)
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        self.insert(0, value).unwrap();
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        self.insert(self.length, value).unwrap();
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.remove(self.length.checked_sub(1)?)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            current: [].iter(),
+            next: self.head.as_deref(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            current: [].iter_mut(),
+            next: self.head.as_deref_mut(),
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for UnrolledList<T, N> {
+    fn drop(&mut self) {
+        let mut iter = self.head.take();
+
+        while let Some(mut i) = iter {
+            iter = i.next.take();
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    current: slice::Iter<'a, T>,
+    next: Option<&'a UnrolledNode<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.current.next() {
+                return Some(value);
+            }
+            let node = self.next.take()?;
+            self.current = node.values.iter();
+            self.next = node.next.as_deref();
+        }
+    }
+}
+
+pub struct IterMut<'a, T> {
+    current: slice::IterMut<'a, T>,
+    next: Option<&'a mut UnrolledNode<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.current.next() {
+                return Some(value);
+            }
+            let node = self.next.take()?;
+            self.current = node.values.iter_mut();
+            self.next = node.next.as_deref_mut();
+        }
+    }
+}
+
+pub struct IntoIter<T> {
+    current: vec::IntoIter<T>,
+    next: Option<Box<UnrolledNode<T>>>,
+}
+
+impl<T, const N: usize> IntoIterator for UnrolledList<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(mut self) -> IntoIter<T> {
+        IntoIter {
+            current: Vec::new().into_iter(),
+            next: self.head.take(),
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(value) = self.current.next() {
+                return Some(value);
+            }
+            let mut node = self.next.take()?;
+            self.next = node.next.take();
+            self.current = std::mem::take(&mut node.values).into_iter();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use crate::collections::linked_list::{List, QueueStackMix};
+    use rand::Rng;
+    use std::time::Instant;
+
+    // Checks the node invariants: no empty nodes and none above capacity.
+    fn check_nodes<T, const N: usize>(list: &UnrolledList<T, N>) {
+        let mut node = list.head.as_deref();
+        let mut total = 0;
+        while let Some(n) = node {
+            assert!(!n.values.is_empty());
+            assert!(n.values.len() <= N);
+            total += n.values.len();
+            node = n.next.as_deref();
+        }
+        assert_eq!(total, list.get_length());
+    }
+
+    #[test]
+    fn against_vec() {
+        let mut rng = rand::rng();
+        let mut list: UnrolledList<u32, 4> = UnrolledList::new();
+        let mut oracle = Vec::new();
+
+        for i in 0..3000 {
+            if oracle.is_empty() || rng.random_bool(0.6) {
+                let index = rng.random_range(0..=oracle.len());
+                assert!(list.insert(index, i).is_ok());
+                oracle.insert(index, i);
+            } else {
+                let index = rng.random_range(0..oracle.len());
+                assert_eq!(list.remove(index), Some(oracle.remove(index)));
+            }
+            assert_eq!(list.get_length(), oracle.len());
+        }
+        check_nodes(&list);
+
+        assert!(list.iter().eq(oracle.iter()));
+        for (index, value) in oracle.iter().enumerate() {
+            assert_eq!(list.get(index), Some(value));
+        }
+        assert_eq!(list.get(oracle.len()), None);
+        assert_eq!(list.insert(oracle.len() + 1, 0), Err(()));
+        assert_eq!(list.remove(oracle.len()), None);
+        assert!(list.into_iter().eq(oracle.into_iter()));
+    }
+
+    #[test]
+    fn push_pop_iter_mut() {
+        let mut list: UnrolledList<u32> = UnrolledList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+
+        for i in 0..100 {
+            list.push_back(i);
+        }
+        list.push_front(1000);
+        check_nodes(&list);
+
+        for v in list.iter_mut() {
+            *v += 1;
+        }
+        if let Some(v) = list.get_mut(1) {
+            *v = 0;
+        }
+        assert_eq!(list.pop_front(), Some(1001));
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.pop_back(), Some(100));
+        assert_eq!(list.iter().sum::<u32>(), (2..100).sum::<u32>());
+    }
+
+    #[test]
+    #[ignore]
+    fn benchmark_against_list_and_vec() {
+        const SIZE: u64 = 1_000_000;
+        const INSERTS: usize = 10_000;
+        let mut rng = rand::rng();
+
+        let mut list = List::new();
+        let mut vector = Vec::new();
+        let mut unrolled: UnrolledList<u64, 64> = UnrolledList::new();
+        for i in 0..SIZE {
+            list.push_to_beginning(i).unwrap();
+            vector.push(i);
+            unrolled.push_front(i);
+        }
+
+        let start = Instant::now();
+        assert_eq!(list.iter().sum::<u64>(), SIZE * (SIZE - 1) / 2);
+        println!("List iteration took {:?}", start.elapsed());
+
+        let start = Instant::now();
+        assert_eq!(vector.iter().sum::<u64>(), SIZE * (SIZE - 1) / 2);
+        println!("Vec iteration took {:?}", start.elapsed());
+
+        let start = Instant::now();
+        assert_eq!(unrolled.iter().sum::<u64>(), SIZE * (SIZE - 1) / 2);
+        println!("UnrolledList iteration took {:?}", start.elapsed());
+
+        let indices: Vec<usize> = (0..INSERTS).map(|_| rng.random_range(0..SIZE as usize)).collect();
+
+        let start = Instant::now();
+        for index in &indices {
+            vector.insert(*index, 0);
+        }
+        println!("{} Vec inserts took {:?}", INSERTS, start.elapsed());
+
+        let start = Instant::now();
+        for index in &indices {
+            unrolled.insert(*index, 0).unwrap();
+        }
+        println!("{} UnrolledList inserts took {:?}", INSERTS, start.elapsed());
+    }
+}
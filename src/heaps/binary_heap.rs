@@ -0,0 +1,190 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeapKind {
+    Min,
+    Max,
+}
+
+// Implicit heap in a Vec where every node has up to `D` children; the children of `i` are
+// `D * i + 1 ..= D * i + D`. Wider heaps are shallower, trading cheaper pushes for costlier pops.
+pub struct DaryHeap<T, const D: usize> {
+    data: Vec<T>,
+    kind: HeapKind,
+}
+
+pub type BinaryHeap<T> = DaryHeap<T, 2>;
+
+impl<T, const D: usize> DaryHeap<T, D> where T: PartialOrd {
+    pub fn new(kind: HeapKind) -> Self {
+        assert!(D >= 2, "a heap node needs at least two children");
+        DaryHeap { data: Vec::new(), kind }
+    }
+
+    pub fn new_min() -> Self {
+        Self::new(HeapKind::Min)
+    }
+
+    pub fn new_max() -> Self {
+        Self::new(HeapKind::Max)
+    }
+
+    // Builds the heap bottom-up in O(n).
+    pub fn from_vec(data: Vec<T>, kind: HeapKind) -> Self {
+        let mut heap = Self::new(kind);
+        heap.data = data;
+        for index in (0..heap.data.len() / D + 1).rev() {
+            heap.sift_down(index, heap.data.len());
+        }
+        heap
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn kind(&self) -> HeapKind {
+        self.kind
+    }
+
+    // Whether `a` belongs above `b`.
+    fn before(&self, a: &T, b: &T) -> bool {
+        match self.kind {
+            HeapKind::Min => a < b,
+            HeapKind::Max => a > b,
+        }
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / D;
+            if !self.before(&self.data[index], &self.data[parent]) {
+                break;
+            }
+            self.data.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    // Only looks at the first `end` values, so the sorted tail of `into_sorted_vec` is left alone.
+    fn sift_down(&mut self, mut index: usize, end: usize) {
+        loop {
+            let first_child = D * index + 1;
+            if first_child >= end {
+                return;
+            }
+
+            let mut best = first_child;
+            for child in first_child + 1..(first_child + D).min(end) {
+                if self.before(&self.data[child], &self.data[best]) {
+                    best = child;
+                }
+            }
+            if !self.before(&self.data[best], &self.data[index]) {
+                return;
+            }
+            self.data.swap(index, best);
+            index = best;
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.data.push(value);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let value = self.data.swap_remove(0);
+        self.sift_down(0, self.data.len());
+        Some(value)
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    // Sorts in place by repeatedly moving the top to the end, then returns the values ascending.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        for end in (1..self.data.len()).rev() {
+            self.data.swap(0, end);
+            self.sift_down(0, end);
+        }
+        if self.kind == HeapKind::Min {
+            self.data.reverse();
+        }
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+
+    fn check_against_sorted<const D: usize>(kind: HeapKind) {
+        let mut rng = rand::rng();
+        let mut heap: DaryHeap<u32, D> = DaryHeap::new(kind);
+        let mut oracle = Vec::new();
+
+        for _ in 0..2000 {
+            if oracle.is_empty() || rng.random_bool(0.6) {
+                let value = rng.random_range(0..100);
+                heap.push(value);
+                oracle.push(value);
+                oracle.sort();
+            } else {
+                let expected = match kind {
+                    HeapKind::Min => oracle.remove(0),
+                    HeapKind::Max => oracle.pop().unwrap(),
+                };
+                assert_eq!(heap.pop(), Some(expected));
+            }
+            assert_eq!(heap.get_length(), oracle.len());
+            let top = match kind {
+                HeapKind::Min => oracle.first(),
+                HeapKind::Max => oracle.last(),
+            };
+            assert_eq!(heap.peek(), top);
+        }
+        assert_eq!(heap.into_sorted_vec(), oracle);
+    }
+
+    #[test]
+    fn binary_heap() {
+        check_against_sorted::<2>(HeapKind::Min);
+        check_against_sorted::<2>(HeapKind::Max);
+    }
+
+    #[test]
+    fn dary_heap() {
+        check_against_sorted::<3>(HeapKind::Min);
+        check_against_sorted::<4>(HeapKind::Max);
+        check_against_sorted::<8>(HeapKind::Min);
+    }
+
+    #[test]
+    fn from_vec() {
+        let values = vec![5, 3, 9, 1, 7, 1, 8];
+        let mut min = BinaryHeap::from_vec(values.clone(), HeapKind::Min);
+        let max: DaryHeap<_, 4> = DaryHeap::from_vec(values, HeapKind::Max);
+        assert_eq!(min.kind(), HeapKind::Min);
+        assert_eq!(max.peek(), Some(&9));
+        assert_eq!(max.into_sorted_vec(), vec![1, 1, 3, 5, 7, 8, 9]);
+
+        assert_eq!(min.pop(), Some(1));
+        assert_eq!(min.pop(), Some(1));
+        assert_eq!(min.pop(), Some(3));
+        assert!(!min.is_empty());
+
+        let mut empty: BinaryHeap<u32> = BinaryHeap::new_max();
+        assert_eq!(empty.pop(), None);
+        assert_eq!(empty.peek(), None);
+        assert!(BinaryHeap::<u32>::new_min().into_sorted_vec().is_empty());
+    }
+}
@@ -0,0 +1,253 @@
+use std::cell::{Ref, RefCell};
+use std::mem::swap;
+use std::rc::{Rc, Weak};
+use crate::heaps::mergeable_heap::MergeableHeap;
+
+type Link<T> = Rc<RefCell<BinomialNode<T>>>;
+
+// Points at the node currently holding a value. decrease_key moves values up by swapping them
+// with their parents, and the locator travels with its value so handles stay valid.
+type Locator<T> = Rc<RefCell<Weak<RefCell<BinomialNode<T>>>>>;
+
+// A binomial tree of order k; `children[i]` is the child tree of order i.
+struct BinomialNode<T> {
+    value: T,
+    locator: Locator<T>,
+    children: Vec<Link<T>>,
+    parent: Weak<RefCell<BinomialNode<T>>>,
+}
+
+// Refers to one value in a binomial heap until it is popped. Passing it to another heap is a
+// logic error.
+pub struct BinomialHandle<T>(Weak<RefCell<Weak<RefCell<BinomialNode<T>>>>>);
+
+impl<T> Clone for BinomialHandle<T> {
+    fn clone(&self) -> Self {
+        BinomialHandle(self.0.clone())
+    }
+}
+
+// Forest with at most one tree per order, like the bits of the heap's length. Melding two heaps
+// adds them like binary numbers, linking equal-order trees as a carry.
+pub struct BinomialHeap<T> {
+    trees: Vec<Option<Link<T>>>,
+    length: usize,
+}
+
+impl<T> BinomialHeap<T> where T: PartialOrd {
+    pub fn new() -> Self {
+        BinomialHeap {
+            trees: Vec::new(),
+            length: 0,
+        }
+    }
+
+    // Makes the root with the larger value the newest child of the other, producing the next order.
+    fn link(mut a: Link<T>, mut b: Link<T>) -> Link<T> {
+        if b.borrow().value < a.borrow().value {
+            swap(&mut a, &mut b);
+        }
+        b.borrow_mut().parent = Rc::downgrade(&a);
+        a.borrow_mut().children.push(b);
+        a
+    }
+
+    fn meld_trees(&mut self, trees: Vec<Option<Link<T>>>) {
+        let mut carry: Option<Link<T>> = None;
+        let orders = self.trees.len().max(trees.len());
+        self.trees.resize_with(orders, || None);
+
+        let mut incoming = trees.into_iter();
+        for order in 0..orders {
+            let mut present: Vec<Link<T>> = Vec::with_capacity(3);
+            present.extend(self.trees[order].take());
+            present.extend(incoming.next().flatten());
+            present.extend(carry.take());
+
+            // Two or three trees of one order: link a pair into the next order, keep any third.
+            if present.len() >= 2 {
+                let b = present.pop().unwrap();
+                let a = present.pop().unwrap();
+                carry = Some(Self::link(a, b));
+            }
+            self.trees[order] = present.pop();
+        }
+        if carry.is_some() {
+            self.trees.push(carry);
+        }
+    }
+
+    fn min_order(&self) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        for (order, tree) in self.trees.iter().enumerate() {
+            if let Some(tree) = tree {
+                if best.is_none_or(|b| tree.borrow().value < self.trees[b].as_ref().unwrap().borrow().value) {
+                    best = Some(order);
+                }
+            }
+        }
+        best
+    }
+
+    pub fn insert(&mut self, value: T) -> BinomialHandle<T> {
+        let locator: Locator<T> = Rc::new(RefCell::new(Weak::new()));
+        let node = Rc::new(RefCell::new(BinomialNode {
+            value,
+            locator: locator.clone(),
+            children: Vec::new(),
+            parent: Weak::new(),
+        }));
+        *locator.borrow_mut() = Rc::downgrade(&node);
+        self.meld_trees(vec![Some(node)]);
+        self.length += 1;
+        BinomialHandle(Rc::downgrade(&locator))
+    }
+
+    pub fn contains(&self, handle: &BinomialHandle<T>) -> bool {
+        handle.0.strong_count() > 0
+    }
+
+    // Fails when the value has been popped or `value` is larger than the current one. Sifts the
+    // value up its tree, which is O(log n) as trees are that deep.
+    pub fn decrease_key(&mut self, handle: &BinomialHandle<T>, value: T) -> Result<(), ()> {
+        let locator = handle.0.upgrade().ok_or(())?;
+        let mut node = locator.borrow().upgrade().unwrap();
+        if value > node.borrow().value {
+            return Err(());
+        }
+        node.borrow_mut().value = value;
+
+        loop {
+            let parent = match node.borrow().parent.upgrade() {
+                Some(parent) => parent,
+                None => break,
+            };
+            let smaller = node.borrow().value < parent.borrow().value;
+            if !smaller {
+                break;
+            }
+            {
+                let mut child = node.borrow_mut();
+                let mut above = parent.borrow_mut();
+                swap(&mut child.value, &mut above.value);
+                swap(&mut child.locator, &mut above.locator);
+                *child.locator.borrow_mut() = Rc::downgrade(&node);
+                *above.locator.borrow_mut() = Rc::downgrade(&parent);
+            }
+            node = parent;
+        }
+        Ok(())
+    }
+}
+
+impl<T> MergeableHeap<T> for BinomialHeap<T> where T: PartialOrd {
+    fn push(&mut self, value: T) {
+        self.insert(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let order = self.min_order()?;
+        let root = self.trees[order].take().unwrap();
+        while self.trees.last().is_some_and(|t| t.is_none()) {
+            self.trees.pop();
+        }
+
+        let root = Rc::try_unwrap(root).ok().unwrap().into_inner();
+        for child in &root.children {
+            child.borrow_mut().parent = Weak::new();
+        }
+        self.meld_trees(root.children.into_iter().map(Some).collect());
+        self.length -= 1;
+        Some(root.value)
+    }
+
+    fn peek(&self) -> Option<impl std::ops::Deref<Target = T> + '_> {
+        self.min_order().map(|order| Ref::map(self.trees[order].as_ref().unwrap().borrow(), |n| &n.value))
+    }
+
+    fn meld(&mut self, mut other: Self) {
+        self.meld_trees(std::mem::take(&mut other.trees));
+        self.length += other.length;
+    }
+
+    fn get_length(&self) -> usize {
+        self.length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn trees_follow_binary_length() {
+        let mut heap = BinomialHeap::new();
+        for value in (0..13).rev() {
+            heap.push(value);
+        }
+        // 13 = 0b1101: one tree each of order 0, 2 and 3.
+        let orders: Vec<bool> = heap.trees.iter().map(|t| t.is_some()).collect();
+        assert_eq!(orders, vec![true, false, true, true]);
+        assert_eq!(heap.trees[3].as_ref().unwrap().borrow().children.len(), 3);
+
+        assert_eq!(heap.pop(), Some(0));
+        assert_eq!(heap.get_length(), 12);
+        let orders: Vec<bool> = heap.trees.iter().map(|t| t.is_some()).collect();
+        assert_eq!(orders, vec![false, false, true, true]);
+    }
+
+    #[test]
+    fn decrease_key() {
+        let mut heap = BinomialHeap::new();
+        let handles: Vec<_> = (0..10).map(|v| heap.insert(v * 10)).collect();
+        assert_eq!(heap.pop(), Some(0));
+
+        assert_eq!(heap.decrease_key(&handles[7], 5), Ok(()));
+        assert_eq!(heap.decrease_key(&handles[7], 6), Err(()));
+        assert_eq!(heap.decrease_key(&handles[0], 0), Err(()));
+        assert!(heap.contains(&handles[7]));
+        assert_eq!(heap.peek().map(|v| *v), Some(5));
+
+        // 90 sits two levels down in the order 3 tree and climbs past both ancestors
+        assert_eq!(heap.decrease_key(&handles[9], 1), Ok(()));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(5));
+        assert!(!heap.contains(&handles[9]));
+        assert!(!heap.contains(&handles[7]));
+        assert_eq!(heap.decrease_key(&handles[8], 7), Ok(()));
+        assert_eq!(heap.pop(), Some(7));
+        assert_eq!(heap.pop(), Some(10));
+    }
+
+    #[test]
+    fn random_decrease_keys_against_vec() {
+        let mut rng = rand::rng();
+        let mut heap = BinomialHeap::new();
+        let mut live: Vec<(BinomialHandle<u32>, u32)> = Vec::new();
+
+        for _ in 0..2000 {
+            match rng.random_range(0..3) {
+                0 => {
+                    let value = rng.random_range(1000..2000);
+                    live.push((heap.insert(value), value));
+                }
+                1 if !live.is_empty() => {
+                    let at = rng.random_range(0..live.len());
+                    let value = rng.random_range(0..=live[at].1);
+                    assert_eq!(heap.decrease_key(&live[at].0, value), Ok(()));
+                    live[at].1 = value;
+                }
+                _ => {
+                    let smallest = live.iter().map(|(_, v)| *v).min();
+                    assert_eq!(heap.pop(), smallest);
+                    if let Some(at) = live.iter().position(|(h, _)| !heap.contains(h)) {
+                        live.remove(at);
+                    }
+                }
+            }
+            assert_eq!(heap.get_length(), live.len());
+        }
+    }
+}
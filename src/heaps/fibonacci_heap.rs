@@ -0,0 +1,382 @@
+use std::cell::{Ref, RefCell};
+use std::mem::swap;
+use std::rc::{Rc, Weak};
+use crate::collections::doubly_linked_list::DoublyLinkedList;
+use crate::heaps::mergeable_heap::MergeableHeap;
+
+type Link<T> = Rc<RefCell<FibonacciNode<T>>>;
+
+struct FibonacciNode<T> {
+    value: T,
+    // Any one child; the children form a ring through `next` and `prev`
+    child: Option<Link<T>>,
+    degree: usize,
+    next: Option<Link<T>>,
+    prev: Weak<RefCell<FibonacciNode<T>>>,
+    parent: Weak<RefCell<FibonacciNode<T>>>,
+    // Set once the node has lost a child since it last became a child itself.
+    marked: bool,
+}
+
+impl<T> FibonacciNode<T> {
+    fn add_child(parent: &Link<T>, child: Link<T>) {
+        {
+            let mut node = child.borrow_mut();
+            node.parent = Rc::downgrade(parent);
+            node.marked = false;
+        }
+        let mut parent = parent.borrow_mut();
+        parent.degree += 1;
+        let first = match &parent.child {
+            Some(first) => first.clone(),
+            None => {
+                {
+                    let mut node = child.borrow_mut();
+                    node.next = Some(child.clone());
+                    node.prev = Rc::downgrade(&child);
+                }
+                parent.child = Some(child);
+                return;
+            }
+        };
+        // Goes in right after `first`
+        let after = first.borrow_mut().next.replace(child.clone()).unwrap();
+        after.borrow_mut().prev = Rc::downgrade(&child);
+        let mut node = child.borrow_mut();
+        node.prev = Rc::downgrade(&first);
+        node.next = Some(after);
+    }
+
+    fn remove_child(parent: &Link<T>, child: &Link<T>) {
+        let (next, prev) = {
+            let mut node = child.borrow_mut();
+            node.parent = Weak::new();
+            node.marked = false;
+            (node.next.take().unwrap(), std::mem::take(&mut node.prev).upgrade().unwrap())
+        };
+        let mut parent = parent.borrow_mut();
+        parent.degree -= 1;
+        if Rc::ptr_eq(&next, child) {
+            parent.child = None;
+            return;
+        }
+        next.borrow_mut().prev = Rc::downgrade(&prev);
+        if parent.child.as_ref().is_some_and(|first| Rc::ptr_eq(first, child)) {
+            parent.child = Some(next.clone());
+        }
+        prev.borrow_mut().next = Some(next);
+    }
+
+    // Opens the ring, which also breaks its reference cycle
+    fn take_children(parent: &Link<T>) -> Vec<Link<T>> {
+        let mut children = Vec::new();
+        let first = {
+            let mut parent = parent.borrow_mut();
+            parent.degree = 0;
+            match parent.child.take() {
+                Some(first) => first,
+                None => return children,
+            }
+        };
+        let mut current = first.clone();
+        loop {
+            let next = {
+                let mut node = current.borrow_mut();
+                node.prev = Weak::new();
+                node.parent = Weak::new();
+                node.next.take().unwrap()
+            };
+            children.push(current);
+            if Rc::ptr_eq(&next, &first) {
+                return children;
+            }
+            current = next;
+        }
+    }
+}
+
+// Refers to one value in a Fibonacci heap until it is popped. Passing it to another heap is a
+// logic error.
+pub struct FibonacciHandle<T>(Weak<RefCell<FibonacciNode<T>>>);
+
+impl<T> Clone for FibonacciHandle<T> {
+    fn clone(&self) -> Self {
+        FibonacciHandle(self.0.clone())
+    }
+}
+
+// Roots live in a doubly linked list so push and meld are O(1); all tree building is deferred to
+// pop, which consolidates the roots into at most one tree per degree.
+pub struct FibonacciHeap<T> {
+    roots: DoublyLinkedList<Link<T>>,
+    min: Option<Link<T>>,
+    length: usize,
+}
+
+impl<T> FibonacciHeap<T> where T: PartialOrd {
+    pub fn new() -> Self {
+        FibonacciHeap {
+            roots: DoublyLinkedList::new(),
+            min: None,
+            length: 0,
+        }
+    }
+
+    fn add_root(&mut self, node: Link<T>) {
+        let is_min = match &self.min {
+            Some(min) => node.borrow().value < min.borrow().value,
+            None => true,
+        };
+        if is_min {
+            self.min = Some(node.clone());
+        }
+        self.roots.insert_last(node).unwrap();
+    }
+
+    pub fn insert(&mut self, value: T) -> FibonacciHandle<T> {
+        let node = Rc::new(RefCell::new(FibonacciNode {
+            value,
+            child: None,
+            degree: 0,
+            next: None,
+            prev: Weak::new(),
+            parent: Weak::new(),
+            marked: false,
+        }));
+        let handle = FibonacciHandle(Rc::downgrade(&node));
+        self.add_root(node);
+        self.length += 1;
+        handle
+    }
+
+    pub fn contains(&self, handle: &FibonacciHandle<T>) -> bool {
+        handle.0.strong_count() > 0
+    }
+
+    // Fails when the value has been popped or `value` is larger than the current one.
+    pub fn decrease_key(&mut self, handle: &FibonacciHandle<T>, value: T) -> Result<(), ()> {
+        let node = handle.0.upgrade().ok_or(())?;
+        if value > node.borrow().value {
+            return Err(());
+        }
+        node.borrow_mut().value = value;
+
+        let parent = node.borrow().parent.upgrade();
+        match parent {
+            Some(parent) if node.borrow().value < parent.borrow().value => {
+                self.cut(&node, &parent);
+                self.cascading_cut(parent);
+            }
+            Some(_) => {}
+            None => {
+                if node.borrow().value < self.min.as_ref().unwrap().borrow().value {
+                    self.min = Some(node);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn cut(&mut self, node: &Link<T>, parent: &Link<T>) {
+        FibonacciNode::remove_child(parent, node);
+        self.add_root(node.clone());
+    }
+
+    // A marked node losing its second child is cut as well, which keeps subtree sizes exponential
+    // in the degree.
+    fn cascading_cut(&mut self, mut node: Link<T>) {
+        loop {
+            let parent = match node.borrow().parent.upgrade() {
+                Some(parent) => parent,
+                None => return,
+            };
+            if !node.borrow().marked {
+                node.borrow_mut().marked = true;
+                return;
+            }
+            self.cut(&node, &parent);
+            node = parent;
+        }
+    }
+
+    fn link(a: Link<T>, b: Link<T>) -> Link<T> {
+        let (mut parent, mut child) = (a, b);
+        if child.borrow().value < parent.borrow().value {
+            swap(&mut parent, &mut child);
+        }
+        FibonacciNode::add_child(&parent, child);
+        parent
+    }
+
+    fn consolidate(&mut self, removed: &Link<T>) {
+        let mut by_degree: Vec<Option<Link<T>>> = Vec::new();
+        let orphans = FibonacciNode::take_children(removed);
+
+        let mut pending = orphans.into_iter();
+        loop {
+            let mut node = match self.roots.get_first() {
+                Some(node) if Rc::ptr_eq(&node, removed) => continue,
+                Some(node) => node,
+                None => match pending.next() {
+                    Some(node) => node,
+                    None => break,
+                },
+            };
+            loop {
+                let degree = node.borrow().degree;
+                if degree >= by_degree.len() {
+                    by_degree.resize_with(degree + 1, || None);
+                }
+                match by_degree[degree].take() {
+                    Some(other) => node = Self::link(node, other),
+                    None => {
+                        by_degree[degree] = Some(node);
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.min = None;
+        for node in by_degree.into_iter().flatten() {
+            self.add_root(node);
+        }
+    }
+}
+
+impl<T> MergeableHeap<T> for FibonacciHeap<T> where T: PartialOrd {
+    fn push(&mut self, value: T) {
+        self.insert(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let min = self.min.take()?;
+        self.consolidate(&min);
+        self.length -= 1;
+        Some(Rc::try_unwrap(min).ok().unwrap().into_inner().value)
+    }
+
+    fn peek(&self) -> Option<impl std::ops::Deref<Target = T> + '_> {
+        self.min.as_ref().map(|min| Ref::map(min.borrow(), |n| &n.value))
+    }
+
+    fn meld(&mut self, mut other: Self) {
+        let other_min = match other.min.take() {
+            Some(min) => min,
+            None => return,
+        };
+        if self.min.as_ref().is_none_or(|min| other_min.borrow().value < min.borrow().value) {
+            self.min = Some(other_min);
+        }
+        self.roots.append(&mut other.roots);
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    fn get_length(&self) -> usize {
+        self.length
+    }
+}
+
+// Cascading cuts can leave deep trees, so unlink without recursion.
+impl<T> Drop for FibonacciHeap<T> {
+    fn drop(&mut self) {
+        self.min = None;
+        let mut stack = Vec::new();
+        while let Some(root) = self.roots.get_first() {
+            stack.push(root);
+            while let Some(node) = stack.pop() {
+                stack.extend(FibonacciNode::take_children(&node));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn decrease_key() {
+        let mut heap = FibonacciHeap::new();
+        let handles: Vec<_> = (0..10).map(|v| heap.insert(v * 10)).collect();
+        assert_eq!(heap.pop(), Some(0));
+        assert_eq!(heap.roots.get_length(), 2);
+
+        assert_eq!(heap.decrease_key(&handles[7], 5), Ok(()));
+        assert_eq!(heap.decrease_key(&handles[7], 6), Err(()));
+        assert_eq!(heap.decrease_key(&handles[0], 0), Err(()));
+        assert!(heap.contains(&handles[7]));
+        assert_eq!(heap.peek().map(|v| *v), Some(5));
+
+        assert_eq!(heap.decrease_key(&handles[9], 1), Ok(()));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(5));
+        assert!(!heap.contains(&handles[9]));
+        assert_eq!(heap.pop(), Some(10));
+    }
+
+    #[test]
+    fn cascading_cut() {
+        let mut heap = FibonacciHeap::new();
+        let handles: Vec<_> = (0..9).map(|v| heap.insert(v)).collect();
+        // Consolidating eight roots leaves the single tree 1{2, 3{4}, 5{6, 7{8}}}.
+        assert_eq!(heap.pop(), Some(0));
+        assert_eq!(heap.roots.get_length(), 1);
+
+        let is_marked = |handle: &FibonacciHandle<u32>| handle.0.upgrade().unwrap().borrow().marked;
+        let is_root = |handle: &FibonacciHandle<u32>| {
+            handle.0.upgrade().unwrap().borrow().parent.upgrade().is_none()
+        };
+
+        assert_eq!(heap.decrease_key(&handles[8], 0), Ok(()));
+        assert!(is_marked(&handles[7]));
+        assert_eq!(heap.decrease_key(&handles[7], 0), Ok(()));
+        assert!(is_marked(&handles[5]));
+        assert_eq!(heap.roots.get_length(), 3);
+
+        // 5 is already marked, so losing 6 cuts 5 from 1 as well.
+        assert_eq!(heap.decrease_key(&handles[6], 0), Ok(()));
+        assert!(is_root(&handles[5]));
+        assert!(!is_marked(&handles[5]));
+        assert_eq!(heap.roots.get_length(), 5);
+
+        let mut drained = Vec::new();
+        while let Some(v) = heap.pop() {
+            drained.push(v);
+        }
+        assert_eq!(drained, vec![0, 0, 0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn random_decrease_keys_against_vec() {
+        let mut rng = rand::rng();
+        let mut heap = FibonacciHeap::new();
+        let mut live: Vec<(FibonacciHandle<u32>, u32)> = Vec::new();
+
+        for _ in 0..2000 {
+            match rng.random_range(0..3) {
+                0 => {
+                    let value = rng.random_range(1000..2000);
+                    live.push((heap.insert(value), value));
+                }
+                1 if !live.is_empty() => {
+                    let at = rng.random_range(0..live.len());
+                    let value = rng.random_range(0..=live[at].1);
+                    assert_eq!(heap.decrease_key(&live[at].0, value), Ok(()));
+                    live[at].1 = value;
+                }
+                _ => {
+                    let smallest = live.iter().map(|(_, v)| *v).min();
+                    assert_eq!(heap.pop(), smallest);
+                    if let Some(at) = live.iter().position(|(h, _)| !heap.contains(h)) {
+                        live.remove(at);
+                    }
+                }
+            }
+            assert_eq!(heap.get_length(), live.len());
+        }
+    }
+}
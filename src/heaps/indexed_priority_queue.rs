@@ -0,0 +1,257 @@
+// Refers to one queued item; stays valid until that item is popped or removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HeapHandle {
+    slot: usize,
+    generation: u32,
+}
+
+struct Entry<T, P> {
+    item: T,
+    priority: P,
+    position: usize,
+}
+
+struct Slot<T, P> {
+    generation: u32,
+    entry: Option<Entry<T, P>>,
+}
+
+// Min-priority queue whose items can be re-prioritised by handle. Items live in `slots` and the
+// binary heap in `heap` orders slot indices; each entry remembers its position in the heap so a
+// handle can find it in O(1).
+pub struct IndexedPriorityQueue<T, P> {
+    slots: Vec<Slot<T, P>>,
+    free: Vec<usize>,
+    heap: Vec<usize>,
+}
+
+impl<T, P> IndexedPriorityQueue<T, P> where P: PartialOrd {
+    pub fn new() -> Self {
+        IndexedPriorityQueue {
+            slots: Vec::new(),
+            free: Vec::new(),
+            heap: Vec::new(),
+        }
+    }
+
+    pub fn get_length(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    fn entry(&self, handle: HeapHandle) -> Option<&Entry<T, P>> {
+        let slot = self.slots.get(handle.slot)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.entry.as_ref()
+    }
+
+    fn priority_at(&self, position: usize) -> &P {
+        &self.slots[self.heap[position]].entry.as_ref().unwrap().priority
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.slots[self.heap[a]].entry.as_mut().unwrap().position = a;
+        self.slots[self.heap[b]].entry.as_mut().unwrap().position = b;
+    }
+
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if self.priority_at(position) < self.priority_at(parent) {
+                self.swap(position, parent);
+                position = parent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let left = 2 * position + 1;
+            let right = left + 1;
+            let mut smallest = position;
+            if left < self.heap.len() && self.priority_at(left) < self.priority_at(smallest) {
+                smallest = left;
+            }
+            if right < self.heap.len() && self.priority_at(right) < self.priority_at(smallest) {
+                smallest = right;
+            }
+            if smallest == position {
+                return;
+            }
+            self.swap(position, smallest);
+            position = smallest;
+        }
+    }
+
+    pub fn push(&mut self, item: T, priority: P) -> HeapHandle {
+        let entry = Entry { item, priority, position: self.heap.len() };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot].entry = Some(entry);
+                slot
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, entry: Some(entry) });
+                self.slots.len() - 1
+            }
+        };
+
+        self.heap.push(slot);
+        self.sift_up(self.heap.len() - 1);
+        HeapHandle { slot, generation: self.slots[slot].generation }
+    }
+
+    pub fn peek(&self) -> Option<(&T, &P)> {
+        let slot = *self.heap.first()?;
+        self.slots[slot].entry.as_ref().map(|e| (&e.item, &e.priority))
+    }
+
+    pub fn pop(&mut self) -> Option<(T, P)> {
+        let slot = *self.heap.first()?;
+        let handle = HeapHandle { slot, generation: self.slots[slot].generation };
+        self.remove(handle)
+    }
+
+    pub fn remove(&mut self, handle: HeapHandle) -> Option<(T, P)> {
+        let position = self.entry(handle)?.position;
+        let last = self.heap.len() - 1;
+        self.swap(position, last);
+        self.heap.pop();
+
+        let slot = &mut self.slots[handle.slot];
+        slot.generation = slot.generation.wrapping_add(1);
+        let entry = slot.entry.take().unwrap();
+        self.free.push(handle.slot);
+
+        if position < self.heap.len() {
+            self.sift_up(position);
+            self.sift_down(position);
+        }
+        Some((entry.item, entry.priority))
+    }
+
+    pub fn contains(&self, handle: HeapHandle) -> bool {
+        self.entry(handle).is_some()
+    }
+
+    pub fn get(&self, handle: HeapHandle) -> Option<&T> {
+        self.entry(handle).map(|e| &e.item)
+    }
+
+    pub fn priority(&self, handle: HeapHandle) -> Option<&P> {
+        self.entry(handle).map(|e| &e.priority)
+    }
+
+    // Fails for stale handles and for priorities that are not lower than the current one.
+    pub fn decrease_key(&mut self, handle: HeapHandle, priority: P) -> Result<(), ()> {
+        let entry = self.entry(handle).ok_or(())?;
+        if priority > entry.priority {
+            return Err(());
+        }
+        self.change_priority(handle, priority)
+    }
+
+    pub fn change_priority(&mut self, handle: HeapHandle, priority: P) -> Result<(), ()> {
+        self.entry(handle).ok_or(())?;
+        let entry = self.slots[handle.slot].entry.as_mut().unwrap();
+        entry.priority = priority;
+        let position = entry.position;
+        self.sift_up(position);
+        self.sift_down(position);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn push_pop_in_priority_order() {
+        let mut queue = IndexedPriorityQueue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+
+        queue.push("c", 3);
+        queue.push("a", 1);
+        queue.push("d", 4);
+        queue.push("b", 2);
+        assert_eq!(queue.get_length(), 4);
+        assert_eq!(queue.peek(), Some((&"a", &1)));
+
+        assert_eq!(queue.pop(), Some(("a", 1)));
+        assert_eq!(queue.pop(), Some(("b", 2)));
+        assert_eq!(queue.pop(), Some(("c", 3)));
+        assert_eq!(queue.pop(), Some(("d", 4)));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn decrease_and_change_priority() {
+        let mut queue = IndexedPriorityQueue::new();
+        let a = queue.push('a', 10);
+        let b = queue.push('b', 20);
+        let c = queue.push('c', 30);
+
+        assert_eq!(queue.decrease_key(c, 5), Ok(()));
+        assert_eq!(queue.decrease_key(c, 6), Err(()));
+        assert_eq!(queue.priority(c), Some(&5));
+        assert_eq!(queue.peek(), Some((&'c', &5)));
+
+        assert_eq!(queue.change_priority(c, 40), Ok(()));
+        assert_eq!(queue.get(a), Some(&'a'));
+        assert_eq!(queue.remove(b), Some(('b', 20)));
+        assert!(!queue.contains(b));
+        assert_eq!(queue.change_priority(b, 1), Err(()));
+
+        assert_eq!(queue.pop(), Some(('a', 10)));
+        assert_eq!(queue.pop(), Some(('c', 40)));
+
+        // A reused slot must not revive old handles.
+        let d = queue.push('d', 1);
+        assert!(queue.contains(d));
+        assert!(!queue.contains(a));
+        assert_eq!(queue.decrease_key(a, 0), Err(()));
+    }
+
+    #[test]
+    fn random_operations_against_vec() {
+        let mut rng = rand::rng();
+        let mut queue = IndexedPriorityQueue::new();
+        let mut oracle: Vec<(HeapHandle, u32, u32)> = Vec::new();
+
+        for item in 0..3000 {
+            match rng.random_range(0..4) {
+                0 | 1 => {
+                    let priority = rng.random_range(0..1000);
+                    oracle.push((queue.push(item, priority), item, priority));
+                }
+                2 if !oracle.is_empty() => {
+                    let at = rng.random_range(0..oracle.len());
+                    let priority = rng.random_range(0..1000);
+                    assert_eq!(queue.change_priority(oracle[at].0, priority), Ok(()));
+                    oracle[at].2 = priority;
+                }
+                _ => {
+                    let popped = queue.pop();
+                    let smallest = oracle.iter().map(|e| e.2).min();
+                    assert_eq!(popped.map(|p| p.1), smallest);
+                    if let Some((item, _)) = popped {
+                        oracle.retain(|e| e.1 != item);
+                    }
+                }
+            }
+            assert_eq!(queue.get_length(), oracle.len());
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::ops::Deref;
+
+// Min-heaps that can absorb another heap of the same kind cheaply. Implementations that keep
+// values behind a RefCell hand out a guard from `peek` instead of a plain reference.
+pub trait MergeableHeap<T> where T: PartialOrd {
+    fn push(&mut self, value: T);
+    fn pop(&mut self) -> Option<T>;
+    fn peek(&self) -> Option<impl Deref<Target = T> + '_>;
+    fn meld(&mut self, other: Self);
+    fn get_length(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.get_length() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use crate::heaps::binomial_heap::BinomialHeap;
+    use crate::heaps::fibonacci_heap::FibonacciHeap;
+    use crate::heaps::pairing_heap::PairingHeap;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Sorted ascending, so the minimum is always at index 0.
+    struct Reference(Vec<u32>);
+
+    impl Reference {
+        fn push(&mut self, value: u32) {
+            let at = self.0.partition_point(|v| *v <= value);
+            self.0.insert(at, value);
+        }
+
+        fn pop(&mut self) -> Option<u32> {
+            if self.0.is_empty() { None } else { Some(self.0.remove(0)) }
+        }
+    }
+
+    fn check_matches<H>(heap: &H, reference: &Reference) where H: MergeableHeap<u32> {
+        assert_eq!(heap.get_length(), reference.0.len());
+        assert_eq!(heap.is_empty(), reference.0.is_empty());
+        assert_eq!(heap.peek().map(|v| *v), reference.0.first().copied());
+    }
+
+    fn empty_heap<H>(new: fn() -> H) where H: MergeableHeap<u32> {
+        let mut heap = new();
+        check_matches(&heap, &Reference(Vec::new()));
+        assert_eq!(heap.pop(), None);
+        heap.meld(new());
+        assert!(heap.is_empty());
+    }
+
+    fn drains_in_order<H>(new: fn() -> H) where H: MergeableHeap<u32> {
+        let mut heap = new();
+        let mut reference = Reference(Vec::new());
+        for value in [5, 3, 8, 1, 9, 1, 7, 3, 0, 12] {
+            heap.push(value);
+            reference.push(value);
+            check_matches(&heap, &reference);
+        }
+        while let Some(expected) = reference.pop() {
+            assert_eq!(heap.pop(), Some(expected));
+            check_matches(&heap, &reference);
+        }
+        assert_eq!(heap.pop(), None);
+    }
+
+    fn random_operations_with_melds<H>(new: fn() -> H) where H: MergeableHeap<u32> {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut heap = new();
+        let mut reference = Reference(Vec::new());
+
+        for _ in 0..3000 {
+            match rng.random_range(0..10) {
+                0..=4 => {
+                    let value = rng.random_range(0..500);
+                    heap.push(value);
+                    reference.push(value);
+                }
+                5..=7 => assert_eq!(heap.pop(), reference.pop()),
+                _ => {
+                    let mut other = new();
+                    for _ in 0..rng.random_range(0..20) {
+                        let value = rng.random_range(0..500);
+                        other.push(value);
+                        reference.push(value);
+                    }
+                    heap.meld(other);
+                }
+            }
+            check_matches(&heap, &reference);
+        }
+        while let Some(expected) = reference.pop() {
+            assert_eq!(heap.pop(), Some(expected));
+        }
+    }
+
+    fn conformance<H>(new: fn() -> H) where H: MergeableHeap<u32> {
+        empty_heap(new);
+        drains_in_order(new);
+        random_operations_with_melds(new);
+    }
+
+    #[test]
+    fn pairing_heap() {
+        conformance(PairingHeap::new);
+    }
+
+    #[test]
+    fn binomial_heap() {
+        conformance(BinomialHeap::new);
+    }
+
+    #[test]
+    fn fibonacci_heap() {
+        conformance(FibonacciHeap::new);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod binary_heap;
+pub mod indexed_priority_queue;
+pub mod mergeable_heap;
+pub mod pairing_heap;
+pub mod binomial_heap;
+pub mod fibonacci_heap;
\ No newline at end of file
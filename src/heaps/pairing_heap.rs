@@ -0,0 +1,259 @@
+use std::cell::{Ref, RefCell};
+use std::mem::swap;
+use std::rc::{Rc, Weak};
+use crate::heaps::mergeable_heap::MergeableHeap;
+
+type Link<T> = Rc<RefCell<PairingNode<T>>>;
+
+struct PairingNode<T> {
+    value: T,
+    // First child; the others follow it through `next`
+    child: Option<Link<T>>,
+    next: Option<Link<T>>,
+    // The previous sibling, or the parent of a first child
+    prev: Weak<RefCell<PairingNode<T>>>,
+}
+
+// Refers to one value in a pairing heap until it is popped. Passing it to another heap is a
+// logic error.
+pub struct PairingHandle<T>(Weak<RefCell<PairingNode<T>>>);
+
+impl<T> Clone for PairingHandle<T> {
+    fn clone(&self) -> Self {
+        PairingHandle(self.0.clone())
+    }
+}
+
+// Heap-ordered multiway tree: meld just makes the larger root the first child of the smaller one,
+// and pop pairs up the orphaned children left to right before melding the pairs right to left.
+// Children form a sibling list with back links, so decrease_key cuts a subtree out in O(1).
+pub struct PairingHeap<T> {
+    root: Option<Link<T>>,
+    length: usize,
+}
+
+impl<T> PairingHeap<T> where T: PartialOrd {
+    pub fn new() -> Self {
+        PairingHeap {
+            root: None,
+            length: 0,
+        }
+    }
+
+    fn meld_nodes(a: Link<T>, b: Link<T>) -> Link<T> {
+        let (mut parent, mut child) = (a, b);
+        if child.borrow().value < parent.borrow().value {
+            swap(&mut parent, &mut child);
+        }
+        let first = parent.borrow_mut().child.take();
+        if let Some(first) = &first {
+            first.borrow_mut().prev = Rc::downgrade(&child);
+        }
+        {
+            let mut child = child.borrow_mut();
+            child.next = first;
+            child.prev = Rc::downgrade(&parent);
+        }
+        parent.borrow_mut().child = Some(child);
+        parent
+    }
+
+    fn meld_root(&mut self, node: Link<T>) {
+        self.root = Some(match self.root.take() {
+            Some(root) => Self::meld_nodes(root, node),
+            None => node,
+        });
+    }
+
+    pub fn insert(&mut self, value: T) -> PairingHandle<T> {
+        let node = Rc::new(RefCell::new(PairingNode {
+            value,
+            child: None,
+            next: None,
+            prev: Weak::new(),
+        }));
+        let handle = PairingHandle(Rc::downgrade(&node));
+        self.meld_root(node);
+        self.length += 1;
+        handle
+    }
+
+    pub fn contains(&self, handle: &PairingHandle<T>) -> bool {
+        handle.0.strong_count() > 0
+    }
+
+    // Fails when the value has been popped or `value` is larger than the current one.
+    pub fn decrease_key(&mut self, handle: &PairingHandle<T>, value: T) -> Result<(), ()> {
+        let node = handle.0.upgrade().ok_or(())?;
+        if value > node.borrow().value {
+            return Err(());
+        }
+        node.borrow_mut().value = value;
+
+        // Only the root has nothing before it
+        let prev = node.borrow().prev.upgrade();
+        if let Some(prev) = prev {
+            let next = {
+                let mut node = node.borrow_mut();
+                node.prev = Weak::new();
+                node.next.take()
+            };
+            if let Some(next) = &next {
+                next.borrow_mut().prev = Rc::downgrade(&prev);
+            }
+            let mut prev = prev.borrow_mut();
+            if prev.child.as_ref().is_some_and(|child| Rc::ptr_eq(child, &node)) {
+                prev.child = next;
+            } else {
+                prev.next = next;
+            }
+            drop(prev);
+            self.meld_root(node);
+        }
+        Ok(())
+    }
+}
+
+impl<T> MergeableHeap<T> for PairingHeap<T> where T: PartialOrd {
+    fn push(&mut self, value: T) {
+        self.insert(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        let mut children = Vec::new();
+        let mut next = root.borrow_mut().child.take();
+        while let Some(child) = next {
+            {
+                let mut child = child.borrow_mut();
+                next = child.next.take();
+                child.prev = Weak::new();
+            }
+            children.push(child);
+        }
+
+        let mut pairs = Vec::with_capacity(children.len() / 2 + 1);
+        let mut iter = children.into_iter();
+        while let Some(first) = iter.next() {
+            match iter.next() {
+                Some(second) => pairs.push(Self::meld_nodes(first, second)),
+                None => pairs.push(first),
+            }
+        }
+        self.root = pairs.into_iter().rev().reduce(Self::meld_nodes);
+
+        self.length -= 1;
+        Some(Rc::try_unwrap(root).ok().unwrap().into_inner().value)
+    }
+
+    fn peek(&self) -> Option<impl std::ops::Deref<Target = T> + '_> {
+        self.root.as_ref().map(|root| Ref::map(root.borrow(), |n| &n.value))
+    }
+
+    fn meld(&mut self, mut other: Self) {
+        if let Some(root) = other.root.take() {
+            self.meld_root(root);
+        }
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    fn get_length(&self) -> usize {
+        self.length
+    }
+}
+
+// Unlinks iteratively, since sibling lists and repeated pops both leave long chains.
+impl<T> Drop for PairingHeap<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Link<T>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let mut node = node.borrow_mut();
+            stack.extend(node.child.take());
+            stack.extend(node.next.take());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn decrease_key() {
+        let mut heap = PairingHeap::new();
+        let handles: Vec<_> = (0..10).map(|v| heap.insert(v * 10)).collect();
+        assert_eq!(heap.pop(), Some(0));
+
+        assert_eq!(heap.decrease_key(&handles[7], 5), Ok(()));
+        assert_eq!(heap.decrease_key(&handles[7], 6), Err(()));
+        assert_eq!(heap.decrease_key(&handles[0], 0), Err(()));
+        assert!(heap.contains(&handles[7]));
+        assert_eq!(heap.peek().map(|v| *v), Some(5));
+
+        assert_eq!(heap.decrease_key(&handles[9], 1), Ok(()));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), Some(5));
+        assert!(!heap.contains(&handles[9]));
+        assert_eq!(heap.pop(), Some(10));
+    }
+
+    #[test]
+    fn random_decrease_keys_against_vec() {
+        let mut rng = rand::rng();
+        let mut heap = PairingHeap::new();
+        let mut live: Vec<(PairingHandle<u32>, u32)> = Vec::new();
+
+        for _ in 0..2000 {
+            match rng.random_range(0..3) {
+                0 => {
+                    let value = rng.random_range(1000..2000);
+                    live.push((heap.insert(value), value));
+                }
+                1 if !live.is_empty() => {
+                    let at = rng.random_range(0..live.len());
+                    let value = rng.random_range(0..=live[at].1);
+                    assert_eq!(heap.decrease_key(&live[at].0, value), Ok(()));
+                    live[at].1 = value;
+                }
+                _ => {
+                    let smallest = live.iter().map(|(_, v)| *v).min();
+                    assert_eq!(heap.pop(), smallest);
+                    if let Some(at) = live.iter().position(|(h, _)| !heap.contains(h)) {
+                        live.remove(at);
+                    }
+                }
+            }
+            assert_eq!(heap.get_length(), live.len());
+        }
+    }
+
+    #[test]
+    fn long_chain_drop() {
+        let mut heap = PairingHeap::new();
+        for value in 0..200_000 {
+            heap.push(value);
+        }
+        heap.pop();
+        drop(heap);
+    }
+
+
+    #[test]
+    fn decrease_keys_among_many_siblings() {
+        // Every insert lands under the root, so each cut has tens of thousands of siblings
+        let mut heap = PairingHeap::new();
+        heap.push(0);
+        let handles: Vec<_> = (1..50_000).map(|v| heap.insert(v)).collect();
+        for (value, handle) in handles.iter().enumerate() {
+            assert_eq!(heap.decrease_key(handle, value), Ok(()));
+        }
+        assert_eq!(heap.pop(), Some(0));
+        for value in 0..49_999 {
+            assert_eq!(heap.pop(), Some(value));
+        }
+        assert!(heap.is_empty());
+    }
+}
@@ -7,13 +7,14 @@ mod sort_algorithms;
 mod collections;
 mod trees;
 mod graphs;
+mod heaps;
 
 fn main() {
     let mut rng = rand::thread_rng();
 
     const SIZE : usize = 4096;
     //let mut p = crate::collections::patricia::PatriciaTree::new();
-    let mut p = crate::collections::loopy_patricia::LoopyPatriciaTree::new();
+    let mut p = crate::collections::bit_trie::PatriciaSet::new();
     let mut vector = Vec::new();
     let start = Instant::now();
 
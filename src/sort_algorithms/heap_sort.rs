@@ -0,0 +1,11 @@
+use crate::heaps::binary_heap::{BinaryHeap, HeapKind};
+use crate::sort_algorithms::sort::Sort;
+
+pub struct HeapSort;
+
+impl<T> Sort<T> for HeapSort where T : PartialOrd {
+    fn sort(vector: &mut Vec<T>) {
+        let values = std::mem::take(vector);
+        *vector = BinaryHeap::from_vec(values, HeapKind::Max).into_sorted_vec();
+    }
+}
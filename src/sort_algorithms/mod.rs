@@ -2,4 +2,6 @@ mod selection_sort;
 mod bubble_sort;
 mod merge_sort;
 mod quick_sort;
+mod heap_sort;
+pub(crate) mod radix_sort;
 mod sort;
@@ -0,0 +1,147 @@
+use bitvec::prelude::*;
+use crate::sort_algorithms::sort::Sort;
+
+// Keys that can be read as a string of bits whose lexicographic order is the order of the keys.
+// A string that is a prefix of another sorts first.
+pub(crate) trait RadixKey {
+    fn bit_length(&self) -> usize;
+    fn bit(&self, index: usize) -> bool;
+}
+
+impl RadixKey for BitSlice<u8, Msb0> {
+    fn bit_length(&self) -> usize {
+        self.len()
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self[index]
+    }
+}
+
+impl RadixKey for BitVec<u8, Msb0> {
+    fn bit_length(&self) -> usize {
+        self.len()
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self[index]
+    }
+}
+
+impl RadixKey for u32 {
+    fn bit_length(&self) -> usize {
+        32
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        (self >> (31 - index)) & 1 == 1
+    }
+}
+
+impl RadixKey for u64 {
+    fn bit_length(&self) -> usize {
+        64
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        (self >> (63 - index)) & 1 == 1
+    }
+}
+
+// Flipping the sign bit puts negative numbers first
+impl RadixKey for i32 {
+    fn bit_length(&self) -> usize {
+        32
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        (*self as u32 ^ 0x8000_0000).bit(index)
+    }
+}
+
+// Most significant bit first. Each range is split in place into the keys that end at the
+// current bit, then those with a 0, then those with a 1; the last two are pushed to be split on
+// the next bit. Not stable.
+pub(crate) fn radix_sort_by_key<T, K, F>(items: &mut [T], key: F) where K: RadixKey + ?Sized, F: Fn(&T) -> &K {
+    let mut ranges = vec![(0, items.len(), 0)];
+    while let Some((start, end, depth)) = ranges.pop() {
+        if end - start < 2 {
+            continue;
+        }
+        // items[start..ended] end here, items[ended..zeros] have a 0, items[ones..end] have a 1
+        let mut ended = start;
+        let mut zeros = start;
+        let mut ones = end;
+        while zeros < ones {
+            let k = key(&items[zeros]);
+            if k.bit_length() == depth {
+                items.swap(ended, zeros);
+                ended += 1;
+                zeros += 1;
+            } else if !k.bit(depth) {
+                zeros += 1;
+            } else {
+                ones -= 1;
+                items.swap(zeros, ones);
+            }
+        }
+        ranges.push((ones, end, depth + 1));
+        ranges.push((ended, ones, depth + 1));
+    }
+}
+
+pub struct RadixSort;
+
+impl<T> Sort<T> for RadixSort where T : PartialOrd + RadixKey {
+    fn sort(vector: &mut Vec<T>) {
+        radix_sort_by_key(vector, |value| value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn bit_strings_against_std_sort() {
+        let mut rng = rand::rng();
+        let mut keys: Vec<BitVec<u8, Msb0>> = (0..3000)
+            .map(|_| (0..rng.random_range(0..20)).map(|_| rng.random_bool(0.5)).collect())
+            .collect();
+        let mut expected = keys.clone();
+        expected.sort();
+        RadixSort::sort(&mut keys);
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn integers_against_std_sort() {
+        let mut rng = rand::rng();
+        let mut signed: Vec<i32> = (0..3000).map(|_| rng.random()).collect();
+        signed.extend([i32::MIN, i32::MAX, 0, -1, 0]);
+        let mut expected = signed.clone();
+        expected.sort();
+        RadixSort::sort(&mut signed);
+        assert_eq!(signed, expected);
+
+        let mut unsigned: Vec<u64> = (0..3000)
+            .map(|_| {
+                let width = rng.random_range(1..64u32);
+                rng.random_range(0..1 << width)
+            })
+            .collect();
+        let mut expected = unsigned.clone();
+        expected.sort();
+        RadixSort::sort(&mut unsigned);
+        assert_eq!(unsigned, expected);
+    }
+
+    #[test]
+    fn sort_by_key_carries_payload() {
+        let mut pairs: Vec<(u32, char)> = vec![(3, 'c'), (1, 'a'), (2, 'b'), (0, 'z')];
+        radix_sort_by_key(&mut pairs, |pair| &pair.0);
+        assert_eq!(pairs, vec![(0, 'z'), (1, 'a'), (2, 'b'), (3, 'c')]);
+    }
+}
@@ -6,8 +6,10 @@ pub trait Sort<T> where T : PartialOrd {
 #[cfg(test)]
 mod tests {
     use crate::sort_algorithms::bubble_sort::BubbleSort;
+    use crate::sort_algorithms::heap_sort::HeapSort;
     use crate::sort_algorithms::merge_sort::MergeSort;
     use crate::sort_algorithms::quick_sort::QuickSort;
+    use crate::sort_algorithms::radix_sort::RadixSort;
     use crate::sort_algorithms::selection_sort::SelectionSort;
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
@@ -154,6 +156,55 @@ mod tests {
         QuickSort::sort(&mut v5);
         QuickSort::sort(&mut v6);
 
+        assert!(verify(v1));
+        assert!(verify(v2));
+        assert!(verify(v3));
+        assert!(verify(v4));
+        assert!(verify(v5));
+        assert!(verify(v6));
+    }
+    #[test]
+    fn heap_sort() {
+        let mut v1 = Vec::<i32>::new();
+        let mut v2 = Vec::<i32>::new();
+        let mut v3 = Vec::<i32>::new();
+        let mut v4 = Vec::<i32>::new();
+        let mut v5 = Vec::<i32>::new();
+        let mut v6 = Vec::<i32>::new();
+        initialise_vectors(&mut v1, &mut v2, &mut v3, &mut v4, &mut v5, &mut v6);
+
+        HeapSort::sort(&mut v1);
+        HeapSort::sort(&mut v2);
+        HeapSort::sort(&mut v3);
+        HeapSort::sort(&mut v4);
+        HeapSort::sort(&mut v5);
+        HeapSort::sort(&mut v6);
+
+        assert!(verify(v1));
+        assert!(verify(v2));
+        assert!(verify(v3));
+        assert!(verify(v4));
+        assert!(verify(v5));
+        assert!(verify(v6));
+    }
+
+    #[test]
+    fn radix_sort() {
+        let mut v1 = Vec::<i32>::new();
+        let mut v2 = Vec::<i32>::new();
+        let mut v3 = Vec::<i32>::new();
+        let mut v4 = Vec::<i32>::new();
+        let mut v5 = Vec::<i32>::new();
+        let mut v6 = Vec::<i32>::new();
+        initialise_vectors(&mut v1, &mut v2, &mut v3, &mut v4, &mut v5, &mut v6);
+
+        RadixSort::sort(&mut v1);
+        RadixSort::sort(&mut v2);
+        RadixSort::sort(&mut v3);
+        RadixSort::sort(&mut v4);
+        RadixSort::sort(&mut v5);
+        RadixSort::sort(&mut v6);
+
         assert!(verify(v1));
         assert!(verify(v2));
         assert!(verify(v3));